@@ -11,24 +11,86 @@ export function main(): Promise<void>;
 */
 export function draw_without_gpu(drawing_json: any, canvas_id: string): Uint8Array;
 /**
+* @param {string} svg
+* @returns {string}
+*/
+export function drawing_from_svg(svg: string): string;
+/**
+* @param {any} drawing_json
+* @param {number} width
+* @param {number} height
+* @param {boolean} with_background
+* @returns {string}
+*/
+export function drawing_to_svg(drawing_json: any, width: number, height: number, with_background: boolean): string;
+/**
+*/
+export enum BackendKind {
+  Auto = 0,
+  Gpu = 1,
+  Cpu = 2,
+  Canvas = 3,
+}
+/**
+*/
+export enum ImageFormat {
+  Png = 0,
+  Jpeg = 1,
+  WebP = 2,
+}
+/**
+*/
+export enum ErrorMetric {
+  Rgb = 0,
+  DeltaE76 = 1,
+  DeltaE2000 = 2,
+  Ssim = 3,
+}
+/**
 */
 export class Engine {
   free(): void;
 /**
+*/
+  reset_stats(): void;
+/**
 */
   toggle_pause(): void;
 /**
-* @param {Uint8Array} source_bytes
-* @param {any} best_drawing
 * @param {number} width
 * @param {number} height
-* @returns {Promise<Engine>}
+* @param {number} supersampling
+* @param {ImageFormat} format
+* @returns {Promise<Uint8Array>}
 */
-  static new(source_bytes: Uint8Array, best_drawing: any, width: number, height: number): Promise<Engine>;
+  export_image(width: number, height: number, supersampling: number, format: ImageFormat): Promise<Uint8Array>;
 /**
+* @param {number} width
+* @param {number} height
+* @returns {string}
+*/
+  best_drawing_svg(width: number, height: number): string;
+/**
+* @param {string} checkpoint
 * @returns {Promise<void>}
 */
-  post_init(): Promise<void>;
+  load_checkpoint(checkpoint: string): Promise<void>;
+/**
+* @returns {Promise<string>}
+*/
+  save_checkpoint(): Promise<string>;
+/**
+* @param {Uint8Array} source_bytes
+* @param {Uint8Array | undefined} mask_bytes
+* @param {any} best_drawing
+* @param {Settings | undefined} settings
+* @param {number | undefined} seed
+* @param {number} width
+* @param {number} height
+* @param {BackendKind} backend
+* @returns {Promise<Engine>}
+*/
+  static new(source_bytes: Uint8Array, mask_bytes: Uint8Array | undefined, best_drawing: any, settings: Settings | undefined, seed: number | undefined, width: number, height: number, backend: BackendKind): Promise<Engine>;
 /**
 * @param {number} max_time_ms
 * @param {string} canvas_id
@@ -36,31 +98,260 @@ export class Engine {
 */
   tick(max_time_ms: number, canvas_id: string): Promise<any>;
 /**
+* @param {Uint8Array | undefined} [mask_bytes]
+* @returns {Promise<void>}
 */
-  reset_stats(): void;
+  set_mask(mask_bytes?: Uint8Array): Promise<void>;
+/**
+* @returns {Promise<void>}
+*/
+  post_init(): Promise<void>;
+/**
+*/
+  settings: Settings;
+}
+/**
+*/
+export class Settings {
+  free(): void;
+/**
+*/
+  constructor();
+/**
+* @returns {string}
+*/
+  to_json(): string;
+/**
+* @param {string} json
+* @returns {Settings}
+*/
+  static from_json(json: string): Settings;
+/**
+*/
+  add_point_probability: number;
+/**
+*/
+  add_polygon_prob: number;
+/**
+*/
+  batch_size: number;
+/**
+*/
+  change_color_prob: number;
+/**
+*/
+  change_fill_probability: number;
+/**
+*/
+  change_stroke_width_probability: number;
+/**
+*/
+  darken_color_prob: number;
+/**
+*/
+  ellipse_share: number;
+/**
+*/
+  error_guided_placement: boolean;
+/**
+*/
+  error_metric: ErrorMetric;
+/**
+*/
+  incremental_evaluation: boolean;
+/**
+*/
+  level_up_stagnation: number;
+/**
+*/
+  lighten_color_prob: number;
+/**
+*/
+  max_alpha: number;
+/**
+*/
+  max_points_per_polygon: number;
+/**
+*/
+  max_polygons_per_image: number;
+/**
+*/
+  micro_adjustment_delta: number;
+/**
+*/
+  micro_adjustment_probability: number;
+/**
+*/
+  min_alpha: number;
+/**
+*/
+  min_points_per_polygon: number;
+/**
+*/
+  min_polygons_per_image: number;
+/**
+*/
+  move_point_max_delta: number;
+/**
+*/
+  move_point_probability: number;
+/**
+*/
+  mutate_background: boolean;
+/**
+*/
+  new_point_max_distance: number;
+/**
+*/
+  offset_polygon_magnitude: number;
+/**
+*/
+  offset_polygon_probability: number;
+/**
+*/
+  per_point_multiplier: number;
+/**
+*/
+  pipeline_depth: number;
+/**
+*/
+  pyramid_levels: number;
+/**
+*/
+  remove_point_probability: number;
+/**
+*/
+  remove_polygon_prob: number;
+/**
+*/
+  reorder_polygon_prob: number;
+/**
+*/
+  resize_ellipse_probability: number;
+/**
+*/
+  rotate_ellipse_probability: number;
+/**
+*/
+  start_with_polygons_per_image: number;
+/**
+*/
+  stroke_share: number;
+/**
+*/
+  transparent_background: boolean;
 }
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
   readonly memory: WebAssembly.Memory;
+  readonly __wbg_get_settings_add_point_probability: (a: number) => number;
+  readonly __wbg_get_settings_add_polygon_prob: (a: number) => number;
+  readonly __wbg_get_settings_batch_size: (a: number) => number;
+  readonly __wbg_get_settings_change_color_prob: (a: number) => number;
+  readonly __wbg_get_settings_change_fill_probability: (a: number) => number;
+  readonly __wbg_get_settings_change_stroke_width_probability: (a: number) => number;
+  readonly __wbg_get_settings_darken_color_prob: (a: number) => number;
+  readonly __wbg_get_settings_ellipse_share: (a: number) => number;
+  readonly __wbg_get_settings_error_guided_placement: (a: number) => number;
+  readonly __wbg_get_settings_error_metric: (a: number) => number;
+  readonly __wbg_get_settings_incremental_evaluation: (a: number) => number;
+  readonly __wbg_get_settings_level_up_stagnation: (a: number) => number;
+  readonly __wbg_get_settings_lighten_color_prob: (a: number) => number;
+  readonly __wbg_get_settings_max_alpha: (a: number) => number;
+  readonly __wbg_get_settings_max_points_per_polygon: (a: number) => number;
+  readonly __wbg_get_settings_max_polygons_per_image: (a: number) => number;
+  readonly __wbg_get_settings_micro_adjustment_delta: (a: number) => number;
+  readonly __wbg_get_settings_micro_adjustment_probability: (a: number) => number;
+  readonly __wbg_get_settings_min_alpha: (a: number) => number;
+  readonly __wbg_get_settings_min_points_per_polygon: (a: number) => number;
+  readonly __wbg_get_settings_min_polygons_per_image: (a: number) => number;
+  readonly __wbg_get_settings_move_point_max_delta: (a: number) => number;
+  readonly __wbg_get_settings_move_point_probability: (a: number) => number;
+  readonly __wbg_get_settings_mutate_background: (a: number) => number;
+  readonly __wbg_get_settings_new_point_max_distance: (a: number) => number;
+  readonly __wbg_get_settings_offset_polygon_magnitude: (a: number) => number;
+  readonly __wbg_get_settings_offset_polygon_probability: (a: number) => number;
+  readonly __wbg_get_settings_per_point_multiplier: (a: number) => number;
+  readonly __wbg_get_settings_pipeline_depth: (a: number) => number;
+  readonly __wbg_get_settings_pyramid_levels: (a: number) => number;
+  readonly __wbg_get_settings_remove_point_probability: (a: number) => number;
+  readonly __wbg_get_settings_remove_polygon_prob: (a: number) => number;
+  readonly __wbg_get_settings_reorder_polygon_prob: (a: number) => number;
+  readonly __wbg_get_settings_resize_ellipse_probability: (a: number) => number;
+  readonly __wbg_get_settings_rotate_ellipse_probability: (a: number) => number;
+  readonly __wbg_get_settings_start_with_polygons_per_image: (a: number) => number;
+  readonly __wbg_get_settings_stroke_share: (a: number) => number;
+  readonly __wbg_get_settings_transparent_background: (a: number) => number;
+  readonly __wbg_set_settings_add_point_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_add_polygon_prob: (a: number, b: number) => void;
+  readonly __wbg_set_settings_batch_size: (a: number, b: number) => void;
+  readonly __wbg_set_settings_change_color_prob: (a: number, b: number) => void;
+  readonly __wbg_set_settings_change_fill_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_change_stroke_width_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_darken_color_prob: (a: number, b: number) => void;
+  readonly __wbg_set_settings_ellipse_share: (a: number, b: number) => void;
+  readonly __wbg_set_settings_error_guided_placement: (a: number, b: number) => void;
+  readonly __wbg_set_settings_error_metric: (a: number, b: number) => void;
+  readonly __wbg_set_settings_incremental_evaluation: (a: number, b: number) => void;
+  readonly __wbg_set_settings_level_up_stagnation: (a: number, b: number) => void;
+  readonly __wbg_set_settings_lighten_color_prob: (a: number, b: number) => void;
+  readonly __wbg_set_settings_max_alpha: (a: number, b: number) => void;
+  readonly __wbg_set_settings_max_points_per_polygon: (a: number, b: number) => void;
+  readonly __wbg_set_settings_max_polygons_per_image: (a: number, b: number) => void;
+  readonly __wbg_set_settings_micro_adjustment_delta: (a: number, b: number) => void;
+  readonly __wbg_set_settings_micro_adjustment_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_min_alpha: (a: number, b: number) => void;
+  readonly __wbg_set_settings_min_points_per_polygon: (a: number, b: number) => void;
+  readonly __wbg_set_settings_min_polygons_per_image: (a: number, b: number) => void;
+  readonly __wbg_set_settings_move_point_max_delta: (a: number, b: number) => void;
+  readonly __wbg_set_settings_move_point_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_mutate_background: (a: number, b: number) => void;
+  readonly __wbg_set_settings_new_point_max_distance: (a: number, b: number) => void;
+  readonly __wbg_set_settings_offset_polygon_magnitude: (a: number, b: number) => void;
+  readonly __wbg_set_settings_offset_polygon_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_per_point_multiplier: (a: number, b: number) => void;
+  readonly __wbg_set_settings_pipeline_depth: (a: number, b: number) => void;
+  readonly __wbg_set_settings_pyramid_levels: (a: number, b: number) => void;
+  readonly __wbg_set_settings_remove_point_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_remove_polygon_prob: (a: number, b: number) => void;
+  readonly __wbg_set_settings_reorder_polygon_prob: (a: number, b: number) => void;
+  readonly __wbg_set_settings_resize_ellipse_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_rotate_ellipse_probability: (a: number, b: number) => void;
+  readonly __wbg_set_settings_start_with_polygons_per_image: (a: number, b: number) => void;
+  readonly __wbg_set_settings_stroke_share: (a: number, b: number) => void;
+  readonly __wbg_set_settings_transparent_background: (a: number, b: number) => void;
+  readonly __wbg_settings_free: (a: number) => void;
+  readonly draw_without_gpu: (a: number, b: number, c: number, d: number) => void;
+  readonly drawing_from_svg: (a: number, b: number, c: number) => void;
+  readonly drawing_to_svg: (a: number, b: number, c: number, d: number, e: number) => void;
+  readonly main: () => void;
+  readonly settings_from_json: (a: number, b: number, c: number) => void;
+  readonly settings_new: () => number;
+  readonly settings_to_json: (a: number, b: number) => void;
   readonly __wbg_engine_free: (a: number) => void;
-  readonly engine_toggle_pause: (a: number) => void;
-  readonly engine_new: (a: number, b: number, c: number, d: number, e: number) => number;
+  readonly engine_best_drawing_svg: (a: number, b: number, c: number, d: number) => void;
+  readonly engine_export_image: (a: number, b: number, c: number, d: number, e: number) => number;
+  readonly engine_load_checkpoint: (a: number, b: number, c: number) => number;
+  readonly engine_new: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number) => number;
   readonly engine_post_init: (a: number) => number;
-  readonly engine_tick: (a: number, b: number, c: number, d: number) => number;
   readonly engine_reset_stats: (a: number) => void;
-  readonly main: () => void;
-  readonly draw_without_gpu: (a: number, b: number, c: number, d: number) => void;
+  readonly engine_save_checkpoint: (a: number) => number;
+  readonly engine_set_mask: (a: number, b: number, c: number) => number;
+  readonly engine_set_settings: (a: number, b: number, c: number) => void;
+  readonly engine_settings: (a: number) => number;
+  readonly engine_tick: (a: number, b: number, c: number, d: number) => number;
+  readonly engine_toggle_pause: (a: number) => void;
   readonly __wbindgen_malloc: (a: number, b: number) => number;
   readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
   readonly __wbindgen_export_2: WebAssembly.Table;
-  readonly _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__h51f4eeaaaf77ab04: (a: number, b: number, c: number) => void;
-  readonly _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__h1e2170f7e2ff2838: (a: number, b: number, c: number) => void;
+  readonly _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__h36d997fc590238ec: (a: number, b: number, c: number) => void;
+  readonly _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__hae9aa00b72b20a92: (a: number, b: number, c: number) => void;
   readonly __wbindgen_add_to_stack_pointer: (a: number) => number;
   readonly __wbindgen_free: (a: number, b: number, c: number) => void;
   readonly __wbindgen_exn_store: (a: number) => void;
-  readonly wasm_bindgen__convert__closures__invoke2_mut__h16f3eaa2e1626db2: (a: number, b: number, c: number, d: number) => void;
+  readonly wasm_bindgen__convert__closures__invoke2_mut__h345fb8444af604b6: (a: number, b: number, c: number, d: number) => void;
   readonly __wbindgen_start: () => void;
 }
 
@@ -1,21 +1,110 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
+export function __wbg_get_settings_add_point_probability(a: number): number;
+export function __wbg_get_settings_add_polygon_prob(a: number): number;
+export function __wbg_get_settings_batch_size(a: number): number;
+export function __wbg_get_settings_change_color_prob(a: number): number;
+export function __wbg_get_settings_change_fill_probability(a: number): number;
+export function __wbg_get_settings_change_stroke_width_probability(a: number): number;
+export function __wbg_get_settings_darken_color_prob(a: number): number;
+export function __wbg_get_settings_ellipse_share(a: number): number;
+export function __wbg_get_settings_error_guided_placement(a: number): number;
+export function __wbg_get_settings_error_metric(a: number): number;
+export function __wbg_get_settings_incremental_evaluation(a: number): number;
+export function __wbg_get_settings_level_up_stagnation(a: number): number;
+export function __wbg_get_settings_lighten_color_prob(a: number): number;
+export function __wbg_get_settings_max_alpha(a: number): number;
+export function __wbg_get_settings_max_points_per_polygon(a: number): number;
+export function __wbg_get_settings_max_polygons_per_image(a: number): number;
+export function __wbg_get_settings_micro_adjustment_delta(a: number): number;
+export function __wbg_get_settings_micro_adjustment_probability(a: number): number;
+export function __wbg_get_settings_min_alpha(a: number): number;
+export function __wbg_get_settings_min_points_per_polygon(a: number): number;
+export function __wbg_get_settings_min_polygons_per_image(a: number): number;
+export function __wbg_get_settings_move_point_max_delta(a: number): number;
+export function __wbg_get_settings_move_point_probability(a: number): number;
+export function __wbg_get_settings_mutate_background(a: number): number;
+export function __wbg_get_settings_new_point_max_distance(a: number): number;
+export function __wbg_get_settings_offset_polygon_magnitude(a: number): number;
+export function __wbg_get_settings_offset_polygon_probability(a: number): number;
+export function __wbg_get_settings_per_point_multiplier(a: number): number;
+export function __wbg_get_settings_pipeline_depth(a: number): number;
+export function __wbg_get_settings_pyramid_levels(a: number): number;
+export function __wbg_get_settings_remove_point_probability(a: number): number;
+export function __wbg_get_settings_remove_polygon_prob(a: number): number;
+export function __wbg_get_settings_reorder_polygon_prob(a: number): number;
+export function __wbg_get_settings_resize_ellipse_probability(a: number): number;
+export function __wbg_get_settings_rotate_ellipse_probability(a: number): number;
+export function __wbg_get_settings_start_with_polygons_per_image(a: number): number;
+export function __wbg_get_settings_stroke_share(a: number): number;
+export function __wbg_get_settings_transparent_background(a: number): number;
+export function __wbg_set_settings_add_point_probability(a: number, b: number): void;
+export function __wbg_set_settings_add_polygon_prob(a: number, b: number): void;
+export function __wbg_set_settings_batch_size(a: number, b: number): void;
+export function __wbg_set_settings_change_color_prob(a: number, b: number): void;
+export function __wbg_set_settings_change_fill_probability(a: number, b: number): void;
+export function __wbg_set_settings_change_stroke_width_probability(a: number, b: number): void;
+export function __wbg_set_settings_darken_color_prob(a: number, b: number): void;
+export function __wbg_set_settings_ellipse_share(a: number, b: number): void;
+export function __wbg_set_settings_error_guided_placement(a: number, b: number): void;
+export function __wbg_set_settings_error_metric(a: number, b: number): void;
+export function __wbg_set_settings_incremental_evaluation(a: number, b: number): void;
+export function __wbg_set_settings_level_up_stagnation(a: number, b: number): void;
+export function __wbg_set_settings_lighten_color_prob(a: number, b: number): void;
+export function __wbg_set_settings_max_alpha(a: number, b: number): void;
+export function __wbg_set_settings_max_points_per_polygon(a: number, b: number): void;
+export function __wbg_set_settings_max_polygons_per_image(a: number, b: number): void;
+export function __wbg_set_settings_micro_adjustment_delta(a: number, b: number): void;
+export function __wbg_set_settings_micro_adjustment_probability(a: number, b: number): void;
+export function __wbg_set_settings_min_alpha(a: number, b: number): void;
+export function __wbg_set_settings_min_points_per_polygon(a: number, b: number): void;
+export function __wbg_set_settings_min_polygons_per_image(a: number, b: number): void;
+export function __wbg_set_settings_move_point_max_delta(a: number, b: number): void;
+export function __wbg_set_settings_move_point_probability(a: number, b: number): void;
+export function __wbg_set_settings_mutate_background(a: number, b: number): void;
+export function __wbg_set_settings_new_point_max_distance(a: number, b: number): void;
+export function __wbg_set_settings_offset_polygon_magnitude(a: number, b: number): void;
+export function __wbg_set_settings_offset_polygon_probability(a: number, b: number): void;
+export function __wbg_set_settings_per_point_multiplier(a: number, b: number): void;
+export function __wbg_set_settings_pipeline_depth(a: number, b: number): void;
+export function __wbg_set_settings_pyramid_levels(a: number, b: number): void;
+export function __wbg_set_settings_remove_point_probability(a: number, b: number): void;
+export function __wbg_set_settings_remove_polygon_prob(a: number, b: number): void;
+export function __wbg_set_settings_reorder_polygon_prob(a: number, b: number): void;
+export function __wbg_set_settings_resize_ellipse_probability(a: number, b: number): void;
+export function __wbg_set_settings_rotate_ellipse_probability(a: number, b: number): void;
+export function __wbg_set_settings_start_with_polygons_per_image(a: number, b: number): void;
+export function __wbg_set_settings_stroke_share(a: number, b: number): void;
+export function __wbg_set_settings_transparent_background(a: number, b: number): void;
+export function __wbg_settings_free(a: number): void;
+export function draw_without_gpu(a: number, b: number, c: number, d: number): void;
+export function drawing_from_svg(a: number, b: number, c: number): void;
+export function drawing_to_svg(a: number, b: number, c: number, d: number, e: number): void;
+export function main(): void;
+export function settings_from_json(a: number, b: number, c: number): void;
+export function settings_new(): number;
+export function settings_to_json(a: number, b: number): void;
 export function __wbg_engine_free(a: number): void;
-export function engine_toggle_pause(a: number): void;
-export function engine_new(a: number, b: number, c: number, d: number, e: number): number;
+export function engine_best_drawing_svg(a: number, b: number, c: number, d: number): void;
+export function engine_export_image(a: number, b: number, c: number, d: number, e: number): number;
+export function engine_load_checkpoint(a: number, b: number, c: number): number;
+export function engine_new(a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number): number;
 export function engine_post_init(a: number): number;
-export function engine_tick(a: number, b: number, c: number, d: number): number;
 export function engine_reset_stats(a: number): void;
-export function main(): void;
-export function draw_without_gpu(a: number, b: number, c: number, d: number): void;
+export function engine_save_checkpoint(a: number): number;
+export function engine_set_mask(a: number, b: number, c: number): number;
+export function engine_set_settings(a: number, b: number, c: number): void;
+export function engine_settings(a: number): number;
+export function engine_tick(a: number, b: number, c: number, d: number): number;
+export function engine_toggle_pause(a: number): void;
 export function __wbindgen_malloc(a: number, b: number): number;
 export function __wbindgen_realloc(a: number, b: number, c: number, d: number): number;
 export const __wbindgen_export_2: WebAssembly.Table;
-export function _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__h51f4eeaaaf77ab04(a: number, b: number, c: number): void;
-export function _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__h1e2170f7e2ff2838(a: number, b: number, c: number): void;
+export function _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__h36d997fc590238ec(a: number, b: number, c: number): void;
+export function _dyn_core__ops__function__FnMut__A____Output___R_as_wasm_bindgen__closure__WasmClosure___describe__invoke__hae9aa00b72b20a92(a: number, b: number, c: number): void;
 export function __wbindgen_add_to_stack_pointer(a: number): number;
 export function __wbindgen_free(a: number, b: number, c: number): void;
 export function __wbindgen_exn_store(a: number): void;
-export function wasm_bindgen__convert__closures__invoke2_mut__h16f3eaa2e1626db2(a: number, b: number, c: number, d: number): void;
+export function wasm_bindgen__convert__closures__invoke2_mut__h345fb8444af604b6(a: number, b: number, c: number, d: number): void;
 export function __wbindgen_start(): void;
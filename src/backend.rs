@@ -0,0 +1,387 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::metric::ErrorMetric;
+use crate::model::drawing::Drawing;
+use crate::model::point::Point;
+use crate::rasterizer::rasterize;
+use crate::Vertex;
+
+pub mod canvas;
+pub mod cpu;
+pub mod gpu;
+
+use canvas::CanvasBackend;
+use cpu::CpuBackend;
+use gpu::GpuBackend;
+
+// which backend the engine uses to render and score drawings
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    Auto, // wgpu if an adapter is available, otherwise Canvas2D in the browser and the CPU rasterizer natively
+    Gpu,  // wgpu render + compute pipelines
+    Cpu,  // software rasterizer, see rasterizer.rs
+    Canvas, // Canvas2D via Drawing::draw, browser only
+}
+
+// pixels of the working size, what score_batch_dirty re-renders and re-diffs (the scissor rect on the GPU)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub const EMPTY: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+    };
+
+    pub fn full(width: usize, height: usize) -> Rect {
+        Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    // every pixel whose center anything within min..max (0..1 coordinates) can touch,
+    // plus one on every side for ellipses' anti-aliased edges and subpixel snapping
+    pub fn covering(min: Point, max: Point, width: usize, height: usize) -> Rect {
+        let from = |v: f32, size: usize| {
+            ((v * size as f32).floor() as isize - 1).clamp(0, size as isize) as usize
+        };
+        let to = |v: f32, size: usize| {
+            ((v * size as f32).ceil() as isize + 1).clamp(0, size as isize) as usize
+        };
+        let (x, y) = (from(min.x, width), from(min.y, height));
+        Rect {
+            x,
+            y,
+            width: to(max.x, width).saturating_sub(x),
+            height: to(max.y, height).saturating_sub(y),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    // row by row, the index of every pixel inside in an image of the given width
+    pub fn indices(&self, width: usize) -> impl Iterator<Item = usize> + '_ {
+        (self.y..self.y + self.height)
+            .flat_map(move |y| (y * width + self.x)..(y * width + self.x + self.width))
+    }
+}
+
+// a batch handed to start_batch, finish_batch turns it into errors
+pub(crate) enum Pending {
+    Ready(Vec<f32>),    // scored before start_batch returned
+    Gpu(gpu::InFlight), // submitted, the totals get read back by finish_batch
+}
+
+pub(crate) trait Backend {
+    // renders the drawing, stays on the backend until drawing_bytes is called
+    async fn render(&mut self, drawing: &Drawing);
+
+    // diffs the last render against the source image, returns the total error
+    async fn score(&mut self) -> f32;
+
+    // renders and scores every drawing, returns their total errors in the same order
+    // what drawing_bytes and error_heatmap refer to afterwards is up to the backend, render + score again if needed
+    async fn score_batch(&mut self, drawings: &[Drawing]) -> Vec<f32> {
+        let mut errors = Vec::with_capacity(drawings.len());
+        for drawing in drawings {
+            self.render(drawing).await;
+            errors.push(self.score().await);
+        }
+        errors
+    }
+
+    // incremental version of score_batch, every drawing only gets rendered and diffed inside its rect
+    // and is assumed to look like the reference everywhere else, see set_reference
+    // the errors are the reference's total plus how much it changed inside the rect
+    // drawing_bytes, errors and error_heatmap don't mean anything afterwards, render + score again if needed
+    async fn score_batch_dirty(&mut self, drawings: &[Drawing], rects: &[Rect]) -> Vec<f32> {
+        let _ = rects; // backends that can't clip just score everything
+        self.score_batch(drawings).await
+    }
+
+    // the last render + score becomes the reference of score_batch_dirty
+    fn set_reference(&mut self) {}
+
+    // score_batch, or score_batch_dirty with rects, split in two so more batches can be started before this one is done
+    // backends that don't work in the background score it right away, dropping it unfinished is fine
+    // the last render + score is undefined afterwards like with score_batch
+    async fn start_batch(&mut self, drawings: &[Drawing], rects: Option<&[Rect]>) -> Pending {
+        Pending::Ready(match rects {
+            Some(rects) => self.score_batch_dirty(drawings, rects).await,
+            None => self.score_batch(drawings).await,
+        })
+    }
+
+    // the errors of a started batch, in the same order as its drawings
+    async fn finish_batch(&mut self, pending: Pending) -> Vec<f32> {
+        match pending {
+            Pending::Ready(errors) => errors,
+            Pending::Gpu(_) => unreachable!("Expected a batch started by this backend."),
+        }
+    }
+
+    // unpadded RGBA8 pixels of the last render
+    async fn drawing_bytes(&mut self) -> Vec<u8>;
+
+    // per pixel error of the last score, after the mask is applied
+    async fn errors(&mut self) -> Vec<f32>;
+
+    // per pixel error of the last score as an image, only needed when it's displayed
+    async fn error_heatmap(&mut self) -> Vec<u8>;
+
+    // renders into a separate target of any size (used for exports), doesn't affect the last render
+    async fn render_vertices(
+        &mut self,
+        vertices: &[Vertex],
+        width: usize,
+        height: usize,
+    ) -> Vec<u8> {
+        rasterize(vertices, width, height)
+    }
+
+    // swaps in another source image, possibly of a different size, the last render and score are gone afterwards
+    // the mask is size specific so it gets dropped too, the metric stays
+    fn set_source(&mut self, source_bytes: &[u8], width: usize, height: usize);
+
+    // what score and error_heatmap measure from now on, ErrorMetric::default() until this gets called
+    fn set_metric(&mut self, metric: ErrorMetric);
+
+    // per pixel weights (one byte each, source size) the error gets multiplied by, None = all 255
+    fn set_mask(&mut self, mask: Option<&[u8]>);
+
+    // largest width or height render_vertices can handle in one go
+    fn max_texture_size(&self) -> usize {
+        usize::MAX
+    }
+}
+
+// the engine picks one at construction time, an enum since async trait fns can't be used as trait objects
+pub(crate) enum AnyBackend {
+    Gpu(Box<GpuBackend>),
+    Cpu(CpuBackend),
+    Canvas(CanvasBackend),
+}
+
+impl AnyBackend {
+    // batch_size and layer_capacity only matter to the GPU, the other backends score batches one drawing at a time
+    pub async fn new(
+        kind: BackendKind,
+        source_bytes: &[u8],
+        width: usize,
+        height: usize,
+        batch_size: usize,
+        layer_capacity: usize,
+    ) -> Self {
+        let backend = match kind {
+            BackendKind::Auto => {
+                match GpuBackend::new(source_bytes, width, height, batch_size, layer_capacity).await
+                {
+                    Some(gpu) => AnyBackend::Gpu(Box::new(gpu)),
+                    None if cfg!(target_arch = "wasm32") => {
+                        AnyBackend::Canvas(CanvasBackend::new(source_bytes, width, height))
+                    }
+                    None => AnyBackend::Cpu(CpuBackend::new(source_bytes, width, height)),
+                }
+            }
+            BackendKind::Gpu => AnyBackend::Gpu(Box::new(
+                GpuBackend::new(source_bytes, width, height, batch_size, layer_capacity)
+                    .await
+                    .expect("Expected a wgpu adapter and device."),
+            )),
+            BackendKind::Cpu => AnyBackend::Cpu(CpuBackend::new(source_bytes, width, height)),
+            BackendKind::Canvas => {
+                AnyBackend::Canvas(CanvasBackend::new(source_bytes, width, height))
+            }
+        };
+        info!("Using the {:?} backend", backend.kind());
+        backend
+    }
+
+    pub fn kind(&self) -> BackendKind {
+        match self {
+            AnyBackend::Gpu(_) => BackendKind::Gpu,
+            AnyBackend::Cpu(_) => BackendKind::Cpu,
+            AnyBackend::Canvas(_) => BackendKind::Canvas,
+        }
+    }
+}
+
+impl Backend for AnyBackend {
+    async fn render(&mut self, drawing: &Drawing) {
+        match self {
+            AnyBackend::Gpu(backend) => backend.render(drawing).await,
+            AnyBackend::Cpu(backend) => backend.render(drawing).await,
+            AnyBackend::Canvas(backend) => backend.render(drawing).await,
+        }
+    }
+
+    async fn score(&mut self) -> f32 {
+        match self {
+            AnyBackend::Gpu(backend) => backend.score().await,
+            AnyBackend::Cpu(backend) => backend.score().await,
+            AnyBackend::Canvas(backend) => backend.score().await,
+        }
+    }
+
+    async fn score_batch(&mut self, drawings: &[Drawing]) -> Vec<f32> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.score_batch(drawings).await,
+            AnyBackend::Cpu(backend) => backend.score_batch(drawings).await,
+            AnyBackend::Canvas(backend) => backend.score_batch(drawings).await,
+        }
+    }
+
+    async fn score_batch_dirty(&mut self, drawings: &[Drawing], rects: &[Rect]) -> Vec<f32> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.score_batch_dirty(drawings, rects).await,
+            AnyBackend::Cpu(backend) => backend.score_batch_dirty(drawings, rects).await,
+            AnyBackend::Canvas(backend) => backend.score_batch_dirty(drawings, rects).await,
+        }
+    }
+
+    fn set_reference(&mut self) {
+        match self {
+            AnyBackend::Gpu(backend) => backend.set_reference(),
+            AnyBackend::Cpu(backend) => backend.set_reference(),
+            AnyBackend::Canvas(backend) => backend.set_reference(),
+        }
+    }
+
+    async fn start_batch(&mut self, drawings: &[Drawing], rects: Option<&[Rect]>) -> Pending {
+        match self {
+            AnyBackend::Gpu(backend) => backend.start_batch(drawings, rects).await,
+            AnyBackend::Cpu(backend) => backend.start_batch(drawings, rects).await,
+            AnyBackend::Canvas(backend) => backend.start_batch(drawings, rects).await,
+        }
+    }
+
+    async fn finish_batch(&mut self, pending: Pending) -> Vec<f32> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.finish_batch(pending).await,
+            AnyBackend::Cpu(backend) => backend.finish_batch(pending).await,
+            AnyBackend::Canvas(backend) => backend.finish_batch(pending).await,
+        }
+    }
+
+    async fn drawing_bytes(&mut self) -> Vec<u8> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.drawing_bytes().await,
+            AnyBackend::Cpu(backend) => backend.drawing_bytes().await,
+            AnyBackend::Canvas(backend) => backend.drawing_bytes().await,
+        }
+    }
+
+    async fn errors(&mut self) -> Vec<f32> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.errors().await,
+            AnyBackend::Cpu(backend) => backend.errors().await,
+            AnyBackend::Canvas(backend) => backend.errors().await,
+        }
+    }
+
+    async fn error_heatmap(&mut self) -> Vec<u8> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.error_heatmap().await,
+            AnyBackend::Cpu(backend) => backend.error_heatmap().await,
+            AnyBackend::Canvas(backend) => backend.error_heatmap().await,
+        }
+    }
+    async fn render_vertices(
+        &mut self,
+        vertices: &[Vertex],
+        width: usize,
+        height: usize,
+    ) -> Vec<u8> {
+        match self {
+            AnyBackend::Gpu(backend) => backend.render_vertices(vertices, width, height).await,
+            AnyBackend::Cpu(backend) => backend.render_vertices(vertices, width, height).await,
+            AnyBackend::Canvas(backend) => backend.render_vertices(vertices, width, height).await,
+        }
+    }
+
+    fn set_source(&mut self, source_bytes: &[u8], width: usize, height: usize) {
+        match self {
+            AnyBackend::Gpu(backend) => backend.set_source(source_bytes, width, height),
+            AnyBackend::Cpu(backend) => backend.set_source(source_bytes, width, height),
+            AnyBackend::Canvas(backend) => backend.set_source(source_bytes, width, height),
+        }
+    }
+
+    fn set_metric(&mut self, metric: ErrorMetric) {
+        match self {
+            AnyBackend::Gpu(backend) => backend.set_metric(metric),
+            AnyBackend::Cpu(backend) => backend.set_metric(metric),
+            AnyBackend::Canvas(backend) => backend.set_metric(metric),
+        }
+    }
+
+    fn set_mask(&mut self, mask: Option<&[u8]>) {
+        match self {
+            AnyBackend::Gpu(backend) => backend.set_mask(mask),
+            AnyBackend::Cpu(backend) => backend.set_mask(mask),
+            AnyBackend::Canvas(backend) => backend.set_mask(mask),
+        }
+    }
+
+    fn max_texture_size(&self) -> usize {
+        match self {
+            AnyBackend::Gpu(backend) => backend.max_texture_size(),
+            AnyBackend::Cpu(backend) => backend.max_texture_size(),
+            AnyBackend::Canvas(backend) => backend.max_texture_size(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: usize, y: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn covering_adds_a_pixel_on_every_side() {
+        let (min, max) = (Point { x: 0.25, y: 0.25 }, Point { x: 0.5, y: 0.5 });
+        assert_eq!(Rect::covering(min, max, 8, 8), rect(1, 1, 4, 4));
+        // a point in the middle of a pixel, or right on the edge between two
+        let (min, max) = (Point { x: 0.35, y: 0.1 }, Point { x: 0.35, y: 0.1 });
+        assert_eq!(Rect::covering(min, max, 10, 10), rect(2, 0, 3, 2));
+    }
+
+    #[test]
+    fn covering_clamps_to_the_image() {
+        let (min, max) = (Point { x: -0.5, y: 0.0 }, Point { x: 1.5, y: 1.0 });
+        assert_eq!(Rect::covering(min, max, 8, 6), Rect::full(8, 6));
+        // entirely outside
+        let (min, max) = (Point { x: 2.0, y: 2.0 }, Point { x: 3.0, y: 3.0 });
+        assert!(Rect::covering(min, max, 8, 8).is_empty());
+    }
+
+    #[test]
+    fn indices_go_row_by_row() {
+        let indices: Vec<usize> = rect(1, 1, 2, 2).indices(4).collect();
+        assert_eq!(indices, [5, 6, 9, 10]);
+        assert_eq!(Rect::EMPTY.indices(4).count(), 0);
+    }
+}
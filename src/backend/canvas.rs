@@ -0,0 +1,89 @@
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::metric::ErrorMetric;
+use crate::model::drawing::Drawing;
+use crate::rasterizer::calculate_error;
+use crate::util::{error_heatmap, get_context, premultiply};
+
+use super::Backend;
+
+// draws with Canvas2D on a detached canvas, only works in the browser
+pub struct CanvasBackend {
+    ctx: CanvasRenderingContext2d,
+    width: usize,
+    source_bytes: Vec<u8>,
+    drawing_bytes: Vec<u8>,
+    errors: Vec<f32>,
+    metric: ErrorMetric,
+    mask: Option<Vec<u8>>,
+}
+
+impl CanvasBackend {
+    pub fn new(source_bytes: &[u8], width: usize, height: usize) -> Self {
+        let canvas = web_sys::window()
+            .and_then(|window| window.document())
+            .expect("Canvas backend needs a document.")
+            .create_element("canvas")
+            .unwrap()
+            .dyn_into::<HtmlCanvasElement>()
+            .unwrap();
+        canvas.set_width(width as u32);
+        canvas.set_height(height as u32);
+
+        CanvasBackend {
+            ctx: get_context(&canvas),
+            width,
+            source_bytes: source_bytes.to_vec(),
+            drawing_bytes: vec![0; source_bytes.len()],
+            errors: vec![0.0; source_bytes.len() / 4],
+            metric: ErrorMetric::default(),
+            mask: None,
+        }
+    }
+}
+
+impl Backend for CanvasBackend {
+    async fn render(&mut self, drawing: &Drawing) {
+        // get_image_data hands out straight alpha, the error calculation expects what the GPU renders
+        self.drawing_bytes = drawing.draw(&self.ctx, true).unwrap();
+        premultiply(&mut self.drawing_bytes);
+    }
+
+    async fn score(&mut self) -> f32 {
+        self.errors = calculate_error(
+            &self.source_bytes,
+            &self.drawing_bytes,
+            self.mask.as_deref(),
+            self.width,
+            self.metric,
+        );
+        self.errors.iter().sum()
+    }
+
+    async fn drawing_bytes(&mut self) -> Vec<u8> {
+        self.drawing_bytes.clone()
+    }
+
+    async fn errors(&mut self) -> Vec<f32> {
+        self.errors.clone()
+    }
+
+    async fn error_heatmap(&mut self) -> Vec<u8> {
+        error_heatmap(&self.errors, self.metric.max_error_per_pixel())
+    }
+
+    fn set_source(&mut self, source_bytes: &[u8], width: usize, height: usize) {
+        let metric = self.metric;
+        *self = CanvasBackend::new(source_bytes, width, height);
+        self.metric = metric;
+    }
+
+    fn set_metric(&mut self, metric: ErrorMetric) {
+        self.metric = metric;
+    }
+
+    fn set_mask(&mut self, mask: Option<&[u8]>) {
+        self.mask = mask.map(|mask| mask.to_vec());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::metric::ErrorMetric;
+use crate::model::drawing::Drawing;
+use crate::model::polygon::Triangulation;
+use crate::rasterizer::{calculate_error, calculate_error_in, rasterize, rasterize_scissored_into};
+use crate::util::error_heatmap;
+use crate::Vertex;
+
+use super::{Backend, Rect};
+
+// no GPU needed, runs anywhere (CI, headless servers, browsers without WebGPU)
+pub struct CpuBackend {
+    width: usize,
+    height: usize,
+    source_bytes: Vec<u8>,
+    drawing_bytes: Vec<u8>,
+    errors: Vec<f32>,
+    metric: ErrorMetric,
+    mask: Option<Vec<u8>>,
+    reference_errors: Vec<f32>, // per pixel, see set_reference
+    reference_error: f32,
+    vertices: Vec<Vertex>,        // reused for every drawing
+    triangulation: Triangulation, // reused for every polygon
+    mutant_bytes: Vec<u8>,        // reused for every mutant in score_batch_dirty
+}
+
+impl CpuBackend {
+    pub fn new(source_bytes: &[u8], width: usize, height: usize) -> Self {
+        CpuBackend {
+            width,
+            height,
+            source_bytes: source_bytes.to_vec(),
+            drawing_bytes: vec![0; source_bytes.len()],
+            errors: vec![0.0; source_bytes.len() / 4],
+            metric: ErrorMetric::default(),
+            mask: None,
+            reference_errors: vec![0.0; source_bytes.len() / 4],
+            reference_error: 0.0,
+            vertices: vec![],
+            triangulation: Triangulation::default(),
+            mutant_bytes: vec![0; source_bytes.len()],
+        }
+    }
+}
+
+impl Backend for CpuBackend {
+    async fn render(&mut self, drawing: &Drawing) {
+        self.vertices.clear();
+        drawing.write_vertices(&mut self.vertices, &mut self.triangulation);
+        self.drawing_bytes = rasterize(&self.vertices, self.width, self.height);
+    }
+
+    async fn score(&mut self) -> f32 {
+        self.errors = calculate_error(
+            &self.source_bytes,
+            &self.drawing_bytes,
+            self.mask.as_deref(),
+            self.width,
+            self.metric,
+        );
+        self.errors.iter().sum()
+    }
+
+    async fn score_batch_dirty(&mut self, drawings: &[Drawing], rects: &[Rect]) -> Vec<f32> {
+        let (width, height) = (self.width, self.height);
+        drawings
+            .iter()
+            .zip(rects)
+            .map(|(drawing, &rect)| {
+                self.vertices.clear();
+                drawing.write_vertices(&mut self.vertices, &mut self.triangulation);
+                rasterize_scissored_into(
+                    &mut self.mutant_bytes,
+                    &self.vertices,
+                    width,
+                    height,
+                    rect,
+                );
+                let error = calculate_error_in(
+                    &self.source_bytes,
+                    &self.mutant_bytes,
+                    self.mask.as_deref(),
+                    width,
+                    self.metric,
+                    rect,
+                );
+                let reference: f32 = rect.indices(width).map(|i| self.reference_errors[i]).sum();
+                self.reference_error + (error - reference)
+            })
+            .collect()
+    }
+
+    fn set_reference(&mut self) {
+        self.reference_errors.clone_from(&self.errors);
+        self.reference_error = self.errors.iter().sum();
+    }
+
+    async fn drawing_bytes(&mut self) -> Vec<u8> {
+        self.drawing_bytes.clone()
+    }
+
+    async fn errors(&mut self) -> Vec<f32> {
+        self.errors.clone()
+    }
+
+    async fn error_heatmap(&mut self) -> Vec<u8> {
+        error_heatmap(&self.errors, self.metric.max_error_per_pixel())
+    }
+
+    fn set_source(&mut self, source_bytes: &[u8], width: usize, height: usize) {
+        let metric = self.metric;
+        *self = CpuBackend::new(source_bytes, width, height);
+        self.metric = metric;
+    }
+
+    fn set_metric(&mut self, metric: ErrorMetric) {
+        self.metric = metric;
+    }
+
+    fn set_mask(&mut self, mask: Option<&[u8]>) {
+        self.mask = mask.map(|mask| mask.to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pollster::block_on;
+    use rand::Rng;
+
+    use super::*;
+    use crate::model::color::Color;
+    use crate::model::point::Point;
+    use crate::model::settings::Settings;
+    use crate::model::shape::Shape;
+    use crate::util::{seeded_rng, SeededRng};
+
+    const SIZE: usize = 32;
+
+    fn settings() -> Settings {
+        Settings {
+            start_with_polygons_per_image: 20,
+            ellipse_share: 0.3,
+            stroke_share: 0.2,
+            ..Settings::default()
+        }
+    }
+
+    // noise, so every misplaced pixel shows up in the error
+    fn backend(rng: &mut SeededRng) -> CpuBackend {
+        let source: Vec<u8> = (0..SIZE * SIZE * 4).map(|_| rng.gen()).collect();
+        CpuBackend::new(&source, SIZE, SIZE)
+    }
+
+    // scores mutant both ways against base as the reference
+    fn assert_dirty_matches_full(backend: &mut CpuBackend, base: &Drawing, mutant: &Drawing) {
+        block_on(backend.render(base));
+        block_on(backend.score());
+        backend.set_reference();
+
+        let rect = match base.changed_bounds(mutant) {
+            Some((min, max)) => Rect::covering(min, max, SIZE, SIZE),
+            None => Rect::EMPTY,
+        };
+        let dirty = block_on(backend.score_batch_dirty(std::slice::from_ref(mutant), &[rect]))[0];
+        let full = block_on(backend.score_batch(std::slice::from_ref(mutant)))[0];
+        // summed up in a different order, only equal up to rounding
+        assert!(
+            (dirty - full).abs() <= full.abs() * 1e-5 + 0.01,
+            "incremental error {} vs full {} in {:?}",
+            dirty,
+            full,
+            rect
+        );
+    }
+
+    // every kind of mutation step can make, on a few random drawings and with and without a mask
+    #[test]
+    fn dirty_scores_match_full_scores() {
+        let settings = settings();
+        let mut rng = seeded_rng(1);
+        for seed in 0..10 {
+            let mut backend = backend(&mut rng);
+            if seed % 2 == 1 {
+                let mask: Vec<u8> = (0..SIZE * SIZE).map(|_| rng.gen()).collect();
+                backend.set_mask(Some(&mask));
+            }
+            let base = Drawing::new_random(Color::WHITE, &settings, &mut rng);
+
+            assert_dirty_matches_full(&mut backend, &base, &base.clone());
+
+            let mut added = base.clone();
+            assert!(added.add_shape(&settings, None, &mut rng));
+            assert_dirty_matches_full(&mut backend, &base, &added);
+
+            let mut removed = base.clone();
+            assert!(removed.remove_shape(&settings, &mut rng));
+            assert_dirty_matches_full(&mut backend, &base, &removed);
+
+            let mut reordered = base.clone();
+            assert!(reordered.reorder_shapes(&mut rng));
+            assert_dirty_matches_full(&mut backend, &base, &reordered);
+
+            let mut moved = base.clone();
+            let polygon = moved.shapes.iter_mut().find_map(|shape| match shape {
+                Shape::Polygon(polygon) => Some(polygon),
+                _ => None,
+            });
+            polygon.expect("Expected a polygon.").points[0] = Point {
+                x: rng.gen(),
+                y: rng.gen(),
+            };
+            assert_dirty_matches_full(&mut backend, &base, &moved);
+
+            let mut background = base.clone();
+            background.background = Color {
+                r: rng.gen(),
+                g: rng.gen(),
+                b: rng.gen(),
+                a: 255,
+            };
+            assert_dirty_matches_full(&mut backend, &base, &background);
+
+            let mut mutated = base.clone();
+            for _ in 0..5 {
+                mutated.mutate(&settings, None, &mut rng);
+            }
+            assert_dirty_matches_full(&mut backend, &base, &mutated);
+        }
+    }
+}
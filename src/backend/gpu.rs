@@ -0,0 +1,1148 @@
+use std::borrow::Cow;
+use std::mem;
+use std::rc::Rc;
+
+use futures_intrusive::channel::shared::{oneshot_channel, OneshotReceiver};
+use wgpu::{vertex_attr_array, BlendState};
+
+use crate::metric::ErrorMetric;
+use crate::model::drawing::Drawing;
+use crate::model::polygon::Triangulation;
+use crate::texture::Texture;
+use crate::util::{error_heatmap, errors_from_gpu, get_bytes, BufferDimensions};
+use crate::Vertex;
+
+use super::{Backend, Pending, Rect};
+
+// number of partial sums produced by the first reduction pass, error.reduce.wgsl sums them up in a second pass
+const REDUCTION_WORKGROUPS: u32 = 256;
+
+// render pipeline for the drawing + compute pipelines diffing it against the source image and summing up the error
+pub struct GpuBackend {
+    width: usize,
+    height: usize,
+    device: Rc<wgpu::Device>, // shared with the backend this one replaces in set_source
+    queue: Rc<wgpu::Queue>,
+    buffer_dimensions: BufferDimensions,
+    drawing_output_buffer: wgpu::Buffer,
+    texture_extent: wgpu::Extent3d,
+    drawing_texture: wgpu::Texture,
+    layer_views: Vec<wgpu::TextureView>,
+    batch_size: usize,
+    vertex_buffer: wgpu::Buffer, // layer_capacity vertices for every layer, only the parts that changed get written
+    layer_capacity: usize,
+    uploaded: Vec<Vec<Vertex>>, // what's in vertex_buffer for every layer
+    vertices: Vec<Vertex>,      // reused for every drawing, swapped with uploaded once written
+    triangulation: Triangulation, // reused for every polygon
+    render_pipeline: wgpu::RenderPipeline,
+    compute_bind_group: wgpu::BindGroup,
+    metric: ErrorMetric,
+    params_buffer: wgpu::Buffer,
+    rects_buffer: wgpu::Buffer,
+    reference_buffer: wgpu::Buffer,
+    reference_error: f32,
+    last_error: f32, // of the drawing in layer 0 when it was last scored
+    mask_texture: wgpu::Texture,
+    compute_pipeline: wgpu::ComputePipeline,
+    error_source_buffer: wgpu::Buffer,
+    error_output_buffer: wgpu::Buffer,
+    reduce_pipeline: wgpu::ComputePipeline,
+    reduce_bind_groups: Vec<(wgpu::BindGroup, wgpu::BindGroup)>,
+    total_error_buffer: wgpu::Buffer,
+    total_error_output_buffer: wgpu::Buffer,
+    readback_buffers: Vec<wgpu::Buffer>, // spare total_error_output_buffers for start_batch, one per batch in flight
+}
+
+// a batch whose totals are being copied back while the GPU works on whatever got submitted after it
+pub struct InFlight {
+    buffer: wgpu::Buffer, // the batch's own total_error_output_buffer, back to readback_buffers once read
+    count: usize,
+    base: f32, // reference_error at submission when incremental, the totals are relative to it
+    submission: wgpu::SubmissionIndex,
+    mapped: OneshotReceiver<Result<(), wgpu::BufferAsyncError>>,
+}
+
+impl GpuBackend {
+    // None if we can't get an adapter or device, e.g. no WebGPU support in the browser or no GPU at all
+    // batch_size is how many drawings score_batch renders and scores with a single submission
+    // layer_capacity is how many vertices vertex_buffer has room for per drawing before it has to grow
+    pub async fn new(
+        source_bytes: &[u8],
+        width: usize,
+        height: usize,
+        batch_size: usize,
+        layer_capacity: usize,
+    ) -> Option<Self> {
+        let backends = wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all);
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            dx12_shader_compiler: wgpu::Dx12Compiler::default(),
+        });
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await?;
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    features: wgpu::Features::empty(),
+                    limits: wgpu::Limits::downlevel_defaults(),
+                },
+                None,
+            )
+            .await
+            .ok()?;
+
+        Some(Self::with_device(
+            Rc::new(device),
+            Rc::new(queue),
+            source_bytes,
+            width,
+            height,
+            batch_size,
+            layer_capacity,
+        ))
+    }
+
+    // everything that depends on the source image and its size, on an existing device
+    fn with_device(
+        device: Rc<wgpu::Device>,
+        queue: Rc<wgpu::Queue>,
+        source_bytes: &[u8],
+        width: usize,
+        height: usize,
+        batch_size: usize,
+        layer_capacity: usize,
+    ) -> Self {
+        // It is a WebGPU requirement that ImageCopyBuffer.layout.bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0
+        // So we calculate padded_bytes_per_row by rounding unpadded_bytes_per_row
+        // up to the next multiple of wgpu::COPY_BYTES_PER_ROW_ALIGNMENT.
+        // https://en.wikipedia.org/wiki/Data_structure_alignment#Computing_padding
+        let buffer_dimensions = BufferDimensions::new(width, height);
+        // The output buffer lets us retrieve the data as an array
+        let drawing_output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (buffer_dimensions.padded_bytes_per_row * buffer_dimensions.height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // per pixel error, gets reduced on the GPU and only copied to error_output_buffer for errors()
+        // one WxH segment for every drawing in a batch
+        let pixels = buffer_dimensions.width * buffer_dimensions.height;
+        let error_source_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (pixels * 4 * batch_size) as u64,
+            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        // final error output per pixel
+        let error_output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (buffer_dimensions.width * buffer_dimensions.height * 4) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // one partial sum per workgroup of the first reduction pass, for every drawing in a batch
+        let partial_error_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("partial_error_buffer"),
+            size: (REDUCTION_WORKGROUPS as usize * mem::size_of::<f32>() * batch_size) as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        // the sum of the whole error buffer, a single f32 per drawing
+        let total_error_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("total_error_buffer"),
+            size: (mem::size_of::<f32>() * batch_size) as u64,
+            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        // the only thing we need to read back for every evaluation
+        let total_error_output_buffer = create_total_error_output_buffer(&device, batch_size);
+
+        let texture_extent = wgpu::Extent3d {
+            width: buffer_dimensions.width as u32,
+            height: buffer_dimensions.height as u32,
+            depth_or_array_layers: 1,
+        };
+
+        let texture_format = wgpu::TextureFormat::Rgba8Unorm;
+
+        // The render pipeline renders data into this texture, one layer per drawing in a batch
+        let drawing_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                depth_or_array_layers: batch_size as u32,
+                ..texture_extent
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: texture_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+            label: None,
+            view_formats: &[],
+        });
+        let view = drawing_texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        // render targets, single evaluations always use layer 0
+        let layer_views = (0..batch_size as u32)
+            .map(|layer| {
+                drawing_texture.create_view(&wgpu::TextureViewDescriptor {
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        // written by upload, one layer_capacity sized part for every layer of drawing_texture
+        let vertex_buffer = create_vertex_buffer(&device, layer_capacity * batch_size);
+
+        let compute_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0, // 'source' bytes loaded from target image
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1, // 'current' render target for drawing, one layer per drawing in a batch
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2Array,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2, // 'error' per pixel error as f32, one WxH segment per drawing in a batch
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: wgpu::BufferSize::new(
+                                (buffer_dimensions.width * buffer_dimensions.height * 4) as u64,
+                            ),
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3, // 'params' ErrorMetric as u32 and whether to subtract the reference, see error.compute2.wgsl
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4, // 'mask' per pixel weight of the error
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 5, // 'rects' the pixels of every layer that count, see score_batch_dirty
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 6, // 'reference' per pixel error of the reference, see set_reference
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("compute_bind_group_layout"),
+            });
+
+        // doubt this makes much sense, attempting to pass in the dimensions as a 1x2 R32Uint texture
+        let dimensions = (width as u32, height as u32);
+        // let dimensions_texture = Texture::from_dimensions(&device, &queue, dimensions).unwrap();
+
+        let source_texture =
+            Texture::from_bytes(&device, &queue, source_bytes, dimensions, &"source").unwrap();
+
+        // 16 bytes rather than 8, the smallest uniform block some (GL) drivers accept
+        let metric = ErrorMetric::default();
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("params_buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&params_buffer, 0, bytemuck::bytes_of(&[metric as u32, 0]));
+
+        // x, y, x + width, y + height for every layer, written by draw
+        let rects_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("rects_buffer"),
+            size: (mem::size_of::<[u32; 4]>() * batch_size) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // copy of the first segment of error_source_buffer, see set_reference
+        let reference_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("reference_buffer"),
+            size: (pixels * mem::size_of::<f32>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // always bound, all white (weight 1 everywhere) until set_mask gets a mask
+        let mask_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: texture_extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: Some("mask"),
+            view_formats: &[],
+        });
+        write_mask(&queue, &mask_texture, &vec![255; width * height]);
+        let mask_view = mask_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &compute_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    // source image texture WxH Rgba8Unorm
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    // render target texture WxHxbatch_size Rgba8Unorm
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    // per pixel error storage buffer, WxH f32 per layer
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer(
+                        error_source_buffer.as_entire_buffer_binding(),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    // mask texture WxH R8Unorm
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&mask_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: rects_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: reference_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("compute_bind_group"),
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+
+        // Load the shaders from disk
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("../shader.wgsl"))),
+        });
+
+        let vertex_buffer_layout = wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Vertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &vertex_attr_array![
+                0 => Float32x4,
+                1 => Float32x4,
+                2 => Float32x4,
+                3 => Float32x4,
+                4 => Float32x4
+            ],
+        };
+
+        let mut primitive = wgpu::PrimitiveState::default();
+        primitive.cull_mode = None;
+
+        // straight alpha vertex colors composited 'over' a premultiplied target, so the target stays premultiplied
+        // (the same as straight alpha once the background is opaque)
+        let blend_state: BlendState = BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+        };
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[vertex_buffer_layout],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: texture_format,
+                    blend: Some(blend_state),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: primitive,
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let compute_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("compute_pipeline_layout"),
+                bind_group_layouts: &[&compute_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let compute_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("../error.compute2.wgsl"))),
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_module,
+            entry_point: "main",
+        });
+
+        let reduce_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0, // 'input' values to sum up
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1, // 'output' one sum per workgroup
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("reduce_bind_group_layout"),
+            });
+
+        // error.reduce.wgsl sums up one segment per drawing (arrayLength / number of drawings),
+        // so the bindings have to be exactly as big as the number of drawings being scored
+        let reduce_bind_groups = (1..=batch_size)
+            .map(|count| {
+                fn binding(buffer: &wgpu::Buffer, values: usize) -> wgpu::BindingResource<'_> {
+                    wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer,
+                        offset: 0,
+                        size: wgpu::BufferSize::new((values * mem::size_of::<f32>()) as u64),
+                    })
+                }
+                let partials = REDUCTION_WORKGROUPS as usize * count;
+
+                // first pass: per pixel error -> partial sums
+                let partial_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &reduce_bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: binding(&error_source_buffer, pixels * count),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: binding(&partial_error_buffer, partials),
+                        },
+                    ],
+                    label: Some("reduce_partial_bind_group"),
+                });
+
+                // second pass: partial sums -> total
+                let total_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &reduce_bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: binding(&partial_error_buffer, partials),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: binding(&total_error_buffer, count),
+                        },
+                    ],
+                    label: Some("reduce_total_bind_group"),
+                });
+
+                (partial_bind_group, total_bind_group)
+            })
+            .collect();
+
+        let reduce_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("reduce_pipeline_layout"),
+                bind_group_layouts: &[&reduce_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let reduce_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("../error.reduce.wgsl"))),
+        });
+
+        let reduce_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("reduce_pipeline"),
+            layout: Some(&reduce_pipeline_layout),
+            module: &reduce_module,
+            entry_point: "main",
+        });
+
+        GpuBackend {
+            width,
+            height,
+            device,
+            queue,
+            buffer_dimensions,
+            drawing_output_buffer,
+            texture_extent,
+            drawing_texture,
+            layer_views,
+            batch_size,
+            vertex_buffer,
+            layer_capacity,
+            uploaded: vec![vec![]; batch_size],
+            vertices: vec![],
+            triangulation: Triangulation::default(),
+            render_pipeline,
+            compute_bind_group,
+            metric,
+            params_buffer,
+            rects_buffer,
+            reference_buffer,
+            reference_error: 0.0,
+            last_error: 0.0,
+            mask_texture,
+            compute_pipeline,
+            error_source_buffer,
+            error_output_buffer,
+            reduce_pipeline,
+            reduce_bind_groups,
+            total_error_buffer,
+            total_error_output_buffer,
+            readback_buffers: vec![],
+        }
+    }
+
+    // renders every drawing into its own layer of drawing_texture, only inside its rect
+    // calculate_error only looks at the pixels inside the rects too
+    async fn draw(&mut self, drawings: &[Drawing], rects: &[Rect]) {
+        assert!(drawings.len() <= self.batch_size);
+        assert_eq!(drawings.len(), rects.len());
+
+        let corners: Vec<[u32; 4]> = rects
+            .iter()
+            .map(|r| [r.x, r.y, r.x + r.width, r.y + r.height].map(|v| v as u32))
+            .collect();
+        self.queue
+            .write_buffer(&self.rects_buffer, 0, bytemuck::cast_slice(&corners));
+
+        for (layer, drawing) in drawings.iter().enumerate() {
+            self.vertices.clear();
+            drawing.write_vertices(&mut self.vertices, &mut self.triangulation);
+            self.upload(layer);
+        }
+
+        let command_buffer: wgpu::CommandBuffer = {
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+            for (layer, (view, rect)) in self.layer_views.iter().zip(rects).enumerate() {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), // opaque drawings bring their own background, see to_vertices
+                            store: true,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                });
+
+                if rect.is_empty() {
+                    continue; // looks like the reference everywhere, cleared is enough
+                }
+                rpass.set_scissor_rect(
+                    rect.x as u32,
+                    rect.y as u32,
+                    rect.width as u32,
+                    rect.height as u32,
+                );
+                rpass.set_pipeline(&self.render_pipeline);
+                // rpass.set_bind_group(0, &self.bind_group, &[]);
+                rpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                let start = (layer * self.layer_capacity) as u32;
+                let range = start..start + self.uploaded[layer].len() as u32;
+                rpass.draw(range, 0..1);
+            }
+
+            encoder.finish()
+        };
+
+        self.queue.submit(Some(command_buffer));
+    }
+
+    // writes self.vertices to the layer's part of vertex_buffer, only from the first to the last vertex that changed
+    // most mutations only touch one shape so that's a small part of the drawing
+    fn upload(&mut self, layer: usize) {
+        if self.vertices.len() > self.layer_capacity {
+            // everything moves, the other layers have to be written again at their new offsets,
+            // including the ones already written for this batch
+            self.layer_capacity = self.vertices.len().next_power_of_two();
+            self.vertex_buffer =
+                create_vertex_buffer(&self.device, self.layer_capacity * self.batch_size);
+            for (other, uploaded) in self
+                .uploaded
+                .iter()
+                .enumerate()
+                .filter(|(_, u)| !u.is_empty())
+            {
+                let offset = other * self.layer_capacity * mem::size_of::<Vertex>();
+                self.queue.write_buffer(
+                    &self.vertex_buffer,
+                    offset as u64,
+                    bytemuck::cast_slice(uploaded),
+                );
+            }
+            self.uploaded[layer].clear();
+        }
+        let (old, new) = (&self.uploaded[layer], &self.vertices);
+        let same = |(a, b): &(&Vertex, &Vertex)| bytemuck::bytes_of(*a) == bytemuck::bytes_of(*b);
+        let start = old.iter().zip(new).take_while(same).count();
+        let end = match old.len() == new.len() {
+            true => {
+                new.len()
+                    - old[start..]
+                        .iter()
+                        .rev()
+                        .zip(new[start..].iter().rev())
+                        .take_while(same)
+                        .count()
+            }
+            false => new.len(),
+        };
+        if start < end {
+            let offset = (layer * self.layer_capacity + start) * mem::size_of::<Vertex>();
+            self.queue.write_buffer(
+                &self.vertex_buffer,
+                offset as u64,
+                bytemuck::cast_slice(&new[start..end]),
+            );
+        }
+        mem::swap(&mut self.uploaded[layer], &mut self.vertices);
+    }
+
+    // error for the first `count` layers of drawing_texture, one total per layer ends up in output
+    // incremental: the reference's per pixel error gets subtracted, the totals are how much the layers differ from it
+    async fn calculate_error(
+        &self,
+        width: u32,
+        height: u32,
+        count: usize,
+        incremental: bool,
+        output: &wgpu::Buffer,
+    ) -> wgpu::SubmissionIndex {
+        self.queue.write_buffer(
+            &self.params_buffer,
+            4,
+            bytemuck::bytes_of(&(incremental as u32)),
+        );
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("calculate_error_command_encoder"),
+            });
+
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Compute Pass"),
+        });
+        cpass.set_pipeline(&self.compute_pipeline);
+        cpass.set_bind_group(0, &self.compute_bind_group, &[]);
+        // compute shader workgroup_size is (8, 8, 1), rounded up so every pixel is covered
+        cpass.dispatch_workgroups(width.div_ceil(8), height.div_ceil(8), count as u32);
+        drop(cpass);
+
+        // parallel reduction, see https://eximia.co/implementing-parallel-reduction-in-cuda/
+        let (partial_bind_group, total_bind_group) = &self.reduce_bind_groups[count - 1];
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Reduce Pass"),
+        });
+        cpass.set_pipeline(&self.reduce_pipeline);
+        cpass.set_bind_group(0, partial_bind_group, &[]);
+        cpass.dispatch_workgroups(REDUCTION_WORKGROUPS, count as u32, 1);
+        cpass.set_bind_group(0, total_bind_group, &[]);
+        cpass.dispatch_workgroups(1, count as u32, 1);
+        drop(cpass);
+
+        encoder.copy_buffer_to_buffer(
+            &self.total_error_buffer,
+            0,
+            output,
+            0,
+            (mem::size_of::<f32>() * count) as u64,
+        );
+
+        self.queue.submit(Some(encoder.finish()))
+    }
+}
+
+impl Backend for GpuBackend {
+    async fn render(&mut self, drawing: &Drawing) {
+        // render pipeline --> draw our triangles to a texture
+        let full = Rect::full(self.width, self.height);
+        self.draw(std::slice::from_ref(drawing), &[full]).await;
+    }
+
+    async fn score(&mut self) -> f32 {
+        // compute pipelines --> diff drawing texture vs source texture and sum it up
+        let output = &self.total_error_output_buffer;
+        self.calculate_error(self.width as u32, self.height as u32, 1, false, output)
+            .await;
+
+        let total_error = get_bytes(&self.device, &self.total_error_output_buffer).await;
+        self.last_error = f32::from_ne_bytes(total_error[..4].try_into().unwrap());
+        self.last_error
+    }
+
+    // one render submission and one compute submission per batch_size drawings, errors read back all at once
+    async fn score_batch(&mut self, drawings: &[Drawing]) -> Vec<f32> {
+        let mut errors = Vec::with_capacity(drawings.len());
+        let full = vec![Rect::full(self.width, self.height); self.batch_size];
+        for batch in drawings.chunks(self.batch_size) {
+            self.draw(batch, &full[..batch.len()]).await;
+            let output = &self.total_error_output_buffer;
+            self.calculate_error(
+                self.width as u32,
+                self.height as u32,
+                batch.len(),
+                false,
+                output,
+            )
+            .await;
+            let total_errors = get_bytes(&self.device, &self.total_error_output_buffer).await;
+            let batch_errors = errors_from_gpu(&total_errors);
+            self.last_error = batch_errors[0];
+            errors.extend(batch_errors.into_iter().take(batch.len()));
+        }
+        errors
+    }
+
+    // same submissions as score_batch, the scissor rects and error shader skip everything outside the rects
+    async fn score_batch_dirty(&mut self, drawings: &[Drawing], rects: &[Rect]) -> Vec<f32> {
+        let mut errors = Vec::with_capacity(drawings.len());
+        for (batch, rects) in drawings
+            .chunks(self.batch_size)
+            .zip(rects.chunks(self.batch_size))
+        {
+            self.draw(batch, rects).await;
+            let output = &self.total_error_output_buffer;
+            self.calculate_error(
+                self.width as u32,
+                self.height as u32,
+                batch.len(),
+                true,
+                output,
+            )
+            .await;
+            let deltas = get_bytes(&self.device, &self.total_error_output_buffer).await;
+            errors.extend(
+                errors_from_gpu(&deltas)
+                    .into_iter()
+                    .take(batch.len())
+                    .map(|delta| self.reference_error + delta),
+            );
+        }
+        errors
+    }
+
+    // layer 0's per pixel error stays on the GPU, only its total is kept here
+    fn set_reference(&mut self) {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(
+            &self.error_source_buffer,
+            0,
+            &self.reference_buffer,
+            0,
+            self.reference_buffer.size(),
+        );
+        self.queue.submit(Some(encoder.finish()));
+        self.reference_error = self.last_error;
+    }
+
+    // one submission like score_batch, the totals get copied to a buffer of their own and mapped from there
+    // so the next batch can be submitted (and use total_error_buffer) before this one is read back
+    async fn start_batch(&mut self, drawings: &[Drawing], rects: Option<&[Rect]>) -> Pending {
+        if drawings.len() > self.batch_size {
+            // more than one submission, not worth keeping in flight
+            return Pending::Ready(match rects {
+                Some(rects) => self.score_batch_dirty(drawings, rects).await,
+                None => self.score_batch(drawings).await,
+            });
+        }
+        let full = vec![Rect::full(self.width, self.height); drawings.len()];
+        self.draw(drawings, rects.unwrap_or(&full)).await;
+
+        let buffer = self
+            .readback_buffers
+            .pop()
+            .unwrap_or_else(|| create_total_error_output_buffer(&self.device, self.batch_size));
+        let (width, height, count) = (self.width as u32, self.height as u32, drawings.len());
+        let submission = self
+            .calculate_error(width, height, count, rects.is_some(), &buffer)
+            .await;
+
+        // mapping starts once the submission is done, the receiver is gone if the batch gets dropped
+        let (sender, mapped) = oneshot_channel();
+        buffer.slice(..).map_async(wgpu::MapMode::Read, move |v| {
+            let _ = sender.send(v);
+        });
+        Pending::Gpu(InFlight {
+            buffer,
+            count,
+            base: match rects {
+                Some(_) => self.reference_error,
+                None => 0.0,
+            },
+            submission,
+            mapped,
+        })
+    }
+
+    async fn finish_batch(&mut self, pending: Pending) -> Vec<f32> {
+        let batch = match pending {
+            Pending::Ready(errors) => return errors,
+            Pending::Gpu(batch) => batch,
+        };
+        // unlike Maintain::Wait this doesn't wait for the batches submitted after this one, no-op on the web
+        self.device
+            .poll(wgpu::Maintain::WaitForSubmissionIndex(batch.submission));
+        let mapped = batch.mapped.receive().await;
+        assert!(
+            matches!(mapped, Some(Ok(()))),
+            "Expected the batch's totals to be mappable."
+        );
+
+        let totals = errors_from_gpu(&batch.buffer.slice(..).get_mapped_range());
+        batch.buffer.unmap();
+        self.readback_buffers.push(batch.buffer); // same batch_size as ours, set_source keeps it
+        let errors: Vec<f32> = totals
+            .into_iter()
+            .take(batch.count)
+            .map(|total| batch.base + total)
+            .collect();
+        // layer 0 is the last render + score if no other batch was started since, same as score_batch
+        self.last_error = errors[0];
+        errors
+    }
+
+    async fn drawing_bytes(&mut self) -> Vec<u8> {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        // Copy the data from the texture to the buffer
+        encoder.copy_texture_to_buffer(
+            self.drawing_texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &self.drawing_output_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.buffer_dimensions.padded_bytes_per_row as u32),
+                    rows_per_image: None,
+                },
+            },
+            self.texture_extent,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let drawing_bytes = get_bytes(&self.device, &self.drawing_output_buffer).await;
+        self.buffer_dimensions.unpad(&drawing_bytes)
+    }
+
+    async fn errors(&mut self) -> Vec<f32> {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(
+            &self.error_source_buffer,
+            0,
+            &self.error_output_buffer,
+            0,
+            self.error_output_buffer.size(),
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let error_buffer = get_bytes(&self.device, &self.error_output_buffer).await;
+        errors_from_gpu(&error_buffer)
+    }
+
+    async fn error_heatmap(&mut self) -> Vec<u8> {
+        error_heatmap(&self.errors().await, self.metric.max_error_per_pixel())
+    }
+    async fn render_vertices(
+        &mut self,
+        vertices: &[Vertex],
+        width: usize,
+        height: usize,
+    ) -> Vec<u8> {
+        // one-off target, exports are rare enough that it's not worth keeping around
+        let buffer_dimensions = BufferDimensions::new(width, height);
+        let extent = wgpu::Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.drawing_texture.format(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            label: Some("export_texture"),
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let output_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("export_output_buffer"),
+            size: (buffer_dimensions.padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let vertex_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            &*self.device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("export_vertex_buffer"),
+                contents: bytemuck::cast_slice(vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            },
+        );
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("export_render_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+            rpass.set_pipeline(&self.render_pipeline);
+            rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            rpass.draw(0..vertices.len() as u32, 0..1);
+        }
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &output_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(buffer_dimensions.padded_bytes_per_row as u32),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let bytes = get_bytes(&self.device, &output_buffer).await;
+        buffer_dimensions.unpad(&bytes)
+    }
+
+    // pipelines get rebuilt too, it only happens a handful of times per run
+    fn set_source(&mut self, source_bytes: &[u8], width: usize, height: usize) {
+        let metric = self.metric;
+        *self = Self::with_device(
+            self.device.clone(),
+            self.queue.clone(),
+            source_bytes,
+            width,
+            height,
+            self.batch_size,
+            self.layer_capacity,
+        );
+        self.set_metric(metric);
+    }
+
+    // picked up by the next calculate_error submission
+    fn set_metric(&mut self, metric: ErrorMetric) {
+        self.metric = metric;
+        self.queue
+            .write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&(metric as u32)));
+    }
+
+    fn set_mask(&mut self, mask: Option<&[u8]>) {
+        match mask {
+            Some(mask) => write_mask(&self.queue, &self.mask_texture, mask),
+            None => write_mask(
+                &self.queue,
+                &self.mask_texture,
+                &vec![255; self.width * self.height],
+            ),
+        }
+    }
+
+    fn max_texture_size(&self) -> usize {
+        self.device.limits().max_texture_dimension_2d as usize
+    }
+}
+
+fn create_total_error_output_buffer(device: &wgpu::Device, batch_size: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("total_error_output_buffer"),
+        size: (mem::size_of::<f32>() * batch_size) as u64,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_vertex_buffer(device: &wgpu::Device, vertices: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("vertex_buffer"),
+        size: (vertices * mem::size_of::<Vertex>()) as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+// one byte per pixel, rows don't need padding for write_texture
+fn write_mask(queue: &wgpu::Queue, texture: &wgpu::Texture, mask: &[u8]) {
+    let size = texture.size();
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            aspect: wgpu::TextureAspect::All,
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+        },
+        mask,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(size.width),
+            rows_per_image: Some(size.height),
+        },
+        size,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use pollster::block_on;
+    use rand::Rng;
+
+    use super::*;
+    use crate::model::color::Color;
+    use crate::model::settings::Settings;
+    use crate::util::seeded_rng;
+
+    const SIZE: usize = 32;
+
+    // vertex_buffer grows twice in the middle of one batch, the first layer has to come along both times
+    #[test]
+    fn growing_keeps_earlier_layers() {
+        let mut rng = seeded_rng(1);
+        let source: Vec<u8> = (0..SIZE * SIZE * 4).map(|_| rng.gen()).collect();
+        let drawings = [1, 20].map(|shapes| {
+            let settings = Settings {
+                start_with_polygons_per_image: shapes,
+                ..Settings::default()
+            };
+            Drawing::new_random(Color::WHITE, &settings, &mut rng)
+        });
+
+        // one device per process, a second GL context doesn't render reliably
+        let Some(mut backend) = block_on(GpuBackend::new(&source, SIZE, SIZE, 2, 6)) else {
+            return; // no adapter to test with
+        };
+        let batch = block_on(backend.score_batch(&drawings));
+        // big enough for both by now, nothing moves
+        for (drawing, error) in drawings.iter().zip(batch) {
+            let alone = block_on(backend.score_batch(std::slice::from_ref(drawing)))[0];
+            assert!(
+                (error - alone).abs() <= alone.abs() * 1e-5,
+                "{} in a batch vs {} alone",
+                error,
+                alone
+            );
+        }
+    }
+}
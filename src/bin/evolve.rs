@@ -0,0 +1,326 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use image::imageops::FilterType;
+use renderer::model::drawing::Drawing;
+use renderer::model::settings::Settings;
+use renderer::{BackendKind, Engine, ImageFormat};
+
+const USAGE: &str = "\
+Usage: evolve <image> [options]
+
+Options:
+  --mask <image>        grayscale importance of every pixel (white = full weight, black = ignored), stretched to the image
+  --drawing <file>      start from a previously saved drawing (json) or an svg illustration
+  --settings <file>     evolution settings (json), missing fields keep their defaults
+  --seed <n>            seed for the mutations, same seed + inputs = same run [default: random]
+  --resume <file>       continue from a checkpoint, overrides --drawing, --settings and --seed
+  --backend <name>      auto, gpu or cpu [default: auto, gpu with a fallback to cpu]
+  --max-size <px>       scale the image down to fit, same as MAX_SIZE in index.ts [default: 384]
+  --generations <n>     stop after n mutations
+  --seconds <s>         stop after s seconds [default: 60 when --generations is not set]
+  --out-json <file>     where to write the best drawing [default: best.json]
+  --out-png <file>      where to write the rendered best drawing [default: best.png]
+  --out-svg <file>      also write the best drawing as svg, at the original image size
+  --export <file>       also render the best drawing at --export-width, png, jpg or webp by extension
+  --export-width <px>   width of --export, the height keeps the aspect ratio [default: original image width]
+  --supersampling <n>   samples per pixel along each axis for --export [default: 2]
+  --out-checkpoint <file>
+                        also write a checkpoint that --resume can pick up again";
+
+struct Args {
+    image: PathBuf,
+    mask: Option<PathBuf>,
+    drawing: Option<PathBuf>,
+    settings: Option<PathBuf>,
+    seed: Option<u32>,
+    resume: Option<PathBuf>,
+    backend: BackendKind,
+    max_size: u32,
+    generations: Option<usize>,
+    seconds: Option<f64>,
+    out_json: PathBuf,
+    out_png: PathBuf,
+    out_svg: Option<PathBuf>,
+    export: Option<(PathBuf, ImageFormat)>,
+    export_width: Option<usize>,
+    supersampling: usize,
+    out_checkpoint: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse() -> Result<Args> {
+        let mut image = None;
+        let mut mask = None;
+        let mut drawing = None;
+        let mut settings = None;
+        let mut seed = None;
+        let mut resume = None;
+        let mut backend = BackendKind::Auto;
+        let mut max_size = 384;
+        let mut generations = None;
+        let mut seconds = None;
+        let mut out_json = PathBuf::from("best.json");
+        let mut out_png = PathBuf::from("best.png");
+        let mut out_svg = None;
+        let mut export = None;
+        let mut export_width = None;
+        let mut supersampling = 2;
+        let mut out_checkpoint = None;
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                "--mask" => mask = Some(PathBuf::from(value()?)),
+                "--drawing" => drawing = Some(PathBuf::from(value()?)),
+                "--settings" => settings = Some(PathBuf::from(value()?)),
+                "--seed" => seed = Some(value()?.parse().context("invalid --seed")?),
+                "--resume" => resume = Some(PathBuf::from(value()?)),
+                "--backend" => {
+                    backend = match value()?.as_str() {
+                        "auto" => BackendKind::Auto,
+                        "gpu" => BackendKind::Gpu,
+                        "cpu" => BackendKind::Cpu,
+                        other => bail!("unknown backend {}, expected auto, gpu or cpu", other),
+                    }
+                }
+                "--max-size" => max_size = value()?.parse().context("invalid --max-size")?,
+                "--generations" => {
+                    generations = Some(value()?.parse().context("invalid --generations")?)
+                }
+                "--seconds" => seconds = Some(value()?.parse().context("invalid --seconds")?),
+                "--out-json" => out_json = PathBuf::from(value()?),
+                "--out-png" => out_png = PathBuf::from(value()?),
+                "--out-svg" => out_svg = Some(PathBuf::from(value()?)),
+                "--export" => {
+                    let path = PathBuf::from(value()?);
+                    let format = path
+                        .extension()
+                        .and_then(|ext| ImageFormat::from_extension(&ext.to_string_lossy()))
+                        .ok_or_else(|| anyhow!("--export has to end in .png, .jpg or .webp"))?;
+                    export = Some((path, format));
+                }
+                "--export-width" => {
+                    export_width = Some(value()?.parse().context("invalid --export-width")?)
+                }
+                "--supersampling" => {
+                    supersampling = value()?.parse().context("invalid --supersampling")?
+                }
+                "--out-checkpoint" => out_checkpoint = Some(PathBuf::from(value()?)),
+                _ if arg.starts_with('-') => bail!("unknown option {}\n\n{}", arg, USAGE),
+                _ => image = Some(PathBuf::from(arg)),
+            }
+        }
+
+        if generations.is_none() && seconds.is_none() {
+            seconds = Some(60.0);
+        }
+
+        Ok(Args {
+            image: image.ok_or_else(|| anyhow!("no input image given\n\n{}", USAGE))?,
+            mask,
+            drawing,
+            settings,
+            seed,
+            resume,
+            backend,
+            max_size,
+            generations,
+            seconds,
+            out_json,
+            out_png,
+            out_svg,
+            export,
+            export_width,
+            supersampling,
+            out_checkpoint,
+        })
+    }
+}
+
+// same as calculateAspectRatioFit in index.ts, including forcing even numbers
+fn fit_dimensions(width: u32, height: u32, max_size: u32) -> (u32, u32) {
+    if width <= max_size && height <= max_size {
+        return (width, height);
+    }
+    let ratio = f64::min(
+        max_size as f64 / width as f64,
+        max_size as f64 / height as f64,
+    );
+    let mut w = (width as f64 * ratio).round() as u32;
+    let mut h = (height as f64 * ratio).round() as u32;
+    if w % 2 != 0 {
+        w += 1;
+    }
+    if h % 2 != 0 {
+        h += 1;
+    }
+    (w, h)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let source = image::open(&args.image)
+        .with_context(|| format!("could not open {}", args.image.display()))?
+        .to_rgba8();
+    let (original_width, original_height) = (source.width() as usize, source.height() as usize);
+    let (w, h) = fit_dimensions(source.width(), source.height(), args.max_size);
+    let source = image::imageops::resize(&source, w, h, FilterType::Triangle);
+
+    let mask = match &args.mask {
+        Some(path) => {
+            let mask = image::open(path)
+                .with_context(|| format!("could not open {}", path.display()))?
+                .to_luma8();
+            Some(image::imageops::resize(&mask, w, h, FilterType::Triangle).into_raw())
+        }
+        None => None,
+    };
+
+    let drawing = match &args.drawing {
+        Some(path) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("could not read {}", path.display()))?;
+            if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+            {
+                Some(Drawing::from_svg(&json)?)
+            } else {
+                Some(json.parse::<Drawing>()?)
+            }
+        }
+        None => None,
+    };
+
+    let settings = match &args.settings {
+        Some(path) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("could not read {}", path.display()))?;
+            json.parse::<Settings>()
+                .with_context(|| format!("invalid settings in {}", path.display()))?
+        }
+        None => Settings::default(),
+    };
+
+    let mut engine = Engine::from_drawing(
+        source.into_raw(),
+        mask,
+        drawing,
+        settings,
+        args.seed,
+        w as usize,
+        h as usize,
+        args.backend,
+    )
+    .await?;
+    match &args.resume {
+        Some(path) => {
+            let checkpoint = std::fs::read_to_string(path)
+                .with_context(|| format!("could not read {}", path.display()))?;
+            engine
+                .load_checkpoint(&checkpoint)
+                .await
+                .with_context(|| format!("could not resume from {}", path.display()))?;
+        }
+        None => {
+            engine.evaluate_best().await;
+        }
+    }
+    println!(
+        "Rendering at: {}x{} ({:?}), seed: {}",
+        w,
+        h,
+        engine.backend(),
+        engine.stats().seed
+    );
+
+    let max_generations = args.generations.unwrap_or(usize::MAX);
+    let max_duration = args
+        .seconds
+        .map(Duration::from_secs_f64)
+        .unwrap_or(Duration::MAX);
+
+    // generated carries over from a checkpoint, --generations counts from here
+    let start_generated = engine.stats().generated;
+    let started = Instant::now();
+    let mut last_report = started;
+    while engine.stats().generated - start_generated < max_generations
+        && started.elapsed() < max_duration
+    {
+        engine.step().await;
+        if last_report.elapsed() >= Duration::from_secs(1) {
+            last_report = Instant::now();
+            let stats = engine.stats();
+            println!(
+                "generated: {}, improvements: {}, fitness: {:.4}, level: {}",
+                stats.generated,
+                stats.improvements,
+                engine.best_drawing().fitness,
+                stats.level
+            );
+        }
+    }
+
+    // the generations still in flight are part of the checkpoint, resuming carries on as if the run hadn't stopped
+    if let Some(path) = &args.out_checkpoint {
+        std::fs::write(path, engine.save_checkpoint().await)
+            .with_context(|| format!("could not write {}", path.display()))?;
+    }
+    // every generated mutant gets compared before the results are written
+    engine.drain().await;
+
+    let best = engine.best_drawing();
+    println!(
+        "Done after {:.1}s, {} mutations, fitness: {:.4}, shapes: {}",
+        started.elapsed().as_secs_f64(),
+        engine.stats().generated,
+        best.fitness,
+        best.shapes.len()
+    );
+
+    std::fs::write(&args.out_json, serde_json::to_string(best)?)
+        .with_context(|| format!("could not write {}", args.out_json.display()))?;
+    // rendered again rather than best_drawing_pixels() which are at the current pyramid level's size
+    let png = engine
+        .export_image(w as usize, h as usize, 1, ImageFormat::Png)
+        .await
+        .context("could not render the png")?;
+    std::fs::write(&args.out_png, png)
+        .with_context(|| format!("could not write {}", args.out_png.display()))?;
+    if let Some(path) = &args.out_svg {
+        std::fs::write(
+            path,
+            engine.best_drawing_svg(original_width, original_height),
+        )
+        .with_context(|| format!("could not write {}", path.display()))?;
+    }
+    if let Some((path, format)) = &args.export {
+        let export_width = args.export_width.unwrap_or(original_width);
+        let export_height = (export_width as f64 * h as f64 / w as f64).round() as usize;
+        let bytes = engine
+            .export_image(export_width, export_height, args.supersampling, *format)
+            .await
+            .with_context(|| format!("could not export {}", path.display()))?;
+        std::fs::write(path, bytes)
+            .with_context(|| format!("could not write {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("warn,renderer=info"),
+    )
+    .init();
+    pollster::block_on(run(Args::parse()?))
+}
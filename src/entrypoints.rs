@@ -1,6 +1,9 @@
-use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsError, JsValue};
 
-use crate::{model::drawing::Drawing, util::get_canvas_by_id};
+use crate::{
+    model::drawing::Drawing,
+    util::{get_canvas_by_id, to_js_error},
+};
 
 #[wasm_bindgen(start)]
 pub async fn main() {
@@ -9,7 +12,8 @@ pub async fn main() {
 }
 
 #[wasm_bindgen()]
-pub fn draw_without_gpu(drawing_json: JsValue, canvas_id: &str) -> Vec<u8> {
+pub fn draw_without_gpu(drawing_json: JsValue, canvas_id: &str) -> Result<Vec<u8>, JsError> {
+    let drawing = Drawing::try_from(drawing_json).map_err(to_js_error)?;
     let canvas = get_canvas_by_id(canvas_id);
 
     let ctx = canvas
@@ -19,5 +23,23 @@ pub fn draw_without_gpu(drawing_json: JsValue, canvas_id: &str) -> Vec<u8> {
         .dyn_into::<web_sys::CanvasRenderingContext2d>()
         .unwrap();
 
-    Drawing::from(drawing_json).draw(&ctx, true).unwrap()
+    Ok(drawing.draw(&ctx, true).unwrap())
+}
+
+#[wasm_bindgen()]
+pub fn drawing_to_svg(
+    drawing_json: JsValue,
+    width: usize,
+    height: usize,
+    with_background: bool,
+) -> Result<String, JsError> {
+    let drawing = Drawing::try_from(drawing_json).map_err(to_js_error)?;
+    Ok(drawing.to_svg(width, height, with_background))
+}
+
+// json that can be passed to Engine::new as best_drawing, throws if the svg can't be used
+#[wasm_bindgen()]
+pub fn drawing_from_svg(svg: &str) -> Result<String, JsError> {
+    let drawing = Drawing::from_svg(svg).map_err(to_js_error)?;
+    Ok(serde_json::to_string(&drawing).expect("Expected serializable Drawing."))
 }
@@ -1,12 +1,199 @@
 @binding(0) @group(0) var source: texture_2d<f32>;
-@binding(1) @group(0) var current: texture_2d<f32>;
+@binding(1) @group(0) var current: texture_2d_array<f32>;
 @binding(2) @group(0) var<storage, read_write> error: array<f32>;
+@binding(3) @group(0) var<uniform> params: Params;
+@binding(4) @group(0) var mask: texture_2d<f32>; // R8Unorm weight per pixel, all 1.0 without a mask
+@binding(5) @group(0) var<storage, read> rects: array<vec4<u32>>; // x, y, x + width, y + height per layer, 0 error outside
+@binding(6) @group(0) var<storage, read> reference: array<f32>; // per pixel error of the reference drawing
 
+struct Params {
+    metric: u32, // ErrorMetric: 0 = Rgb, 1 = DeltaE76, 2 = DeltaE2000, 3 = Ssim
+    incremental: u32, // 1 = the error is the difference to the reference's, see GpuBackend::score_batch_dirty
+};
+
+// same math as metric.rs, see there for the details
+
+const SSIM_RADIUS: i32 = 3;
+const SSIM_C1: f32 = 0.0001;
+const SSIM_C2: f32 = 0.0009;
+
+fn decode(c: f32) -> f32 {
+    if (c <= 0.04045) {
+        return c / 12.92;
+    }
+    return pow((c + 0.055) / 1.055, 2.4);
+}
+
+fn lab_f(t: f32) -> f32 {
+    if (t > 0.008856452) {
+        return pow(t, 1.0 / 3.0);
+    }
+    return t * 7.787037 + 4.0 / 29.0;
+}
+
+fn srgb_to_lab(rgb: vec3<f32>) -> vec3<f32> {
+    let lin = vec3<f32>(decode(rgb.x), decode(rgb.y), decode(rgb.z));
+    let x = dot(vec3<f32>(0.4124564, 0.3575761, 0.1804375), lin) / 0.95047;
+    let y = dot(vec3<f32>(0.2126729, 0.7151522, 0.0721750), lin);
+    let z = dot(vec3<f32>(0.0193339, 0.119192, 0.9503041), lin) / 1.08883;
+    let f = vec3<f32>(lab_f(x), lab_f(y), lab_f(z));
+    return vec3<f32>(116.0 * f.y - 16.0, 500.0 * (f.x - f.y), 200.0 * (f.y - f.z));
+}
+
+fn hue(b: f32, a: f32) -> f32 {
+    if (a == 0.0 && b == 0.0) {
+        return 0.0;
+    }
+    let h = degrees(atan2(b, a));
+    return select(h, h + 360.0, h < 0.0);
+}
+
+fn delta_e2000(lab1: vec3<f32>, lab2: vec3<f32>) -> f32 {
+    let pow25_7 = 6103515625.0;
+
+    let c_bar7 = pow((length(lab1.yz) + length(lab2.yz)) * 0.5, 7.0);
+    let g = 0.5 * (1.0 - sqrt(c_bar7 / (c_bar7 + pow25_7)));
+    let a1 = (1.0 + g) * lab1.y;
+    let a2 = (1.0 + g) * lab2.y;
+    let c1 = length(vec2<f32>(a1, lab1.z));
+    let c2 = length(vec2<f32>(a2, lab2.z));
+    let h1 = hue(lab1.z, a1);
+    let h2 = hue(lab2.z, a2);
+
+    let dl = lab2.x - lab1.x;
+    let dc = c2 - c1;
+    var dh = h2 - h1;
+    if (c1 * c2 == 0.0) {
+        dh = 0.0;
+    } else if (dh > 180.0) {
+        dh -= 360.0;
+    } else if (dh < -180.0) {
+        dh += 360.0;
+    }
+    let dh_big = 2.0 * sqrt(c1 * c2) * sin(radians(dh * 0.5));
+
+    let l_bar = (lab1.x + lab2.x) * 0.5;
+    let c_bar = (c1 + c2) * 0.5;
+    var h_bar = h1 + h2;
+    if (c1 * c2 != 0.0) {
+        if (abs(h1 - h2) <= 180.0) {
+            h_bar = (h1 + h2) * 0.5;
+        } else if (h1 + h2 < 360.0) {
+            h_bar = (h1 + h2 + 360.0) * 0.5;
+        } else {
+            h_bar = (h1 + h2 - 360.0) * 0.5;
+        }
+    }
+
+    let t = 1.0 - 0.17 * cos(radians(h_bar - 30.0))
+        + 0.24 * cos(radians(2.0 * h_bar))
+        + 0.32 * cos(radians(3.0 * h_bar + 6.0))
+        - 0.20 * cos(radians(4.0 * h_bar - 63.0));
+    let hd = (h_bar - 275.0) / 25.0;
+    let d_theta = 30.0 * exp(-hd * hd);
+    let c_bar_7 = pow(c_bar, 7.0);
+    let rc = 2.0 * sqrt(c_bar_7 / (c_bar_7 + pow25_7));
+    let l50 = (l_bar - 50.0) * (l_bar - 50.0);
+    let sl = 1.0 + 0.015 * l50 / sqrt(20.0 + l50);
+    let sc = 1.0 + 0.045 * c_bar;
+    let sh = 1.0 + 0.015 * c_bar * t;
+    let rt = -sin(radians(2.0 * d_theta)) * rc;
+
+    let l = dl / sl;
+    let c = dc / sc;
+    let h = dh_big / sh;
+    return sqrt(max(l * l + c * c + h * h + rt * c * h, 0.0));
+}
+
+fn luma(rgb: vec3<f32>) -> f32 {
+    return rgb.x * 0.299 + rgb.y * 0.587 + rgb.z * 0.114;
+}
+
+fn ssim(sa: f32, sb: f32, saa: f32, sbb: f32, sab: f32) -> f32 {
+    let n = f32((2 * SSIM_RADIUS + 1) * (2 * SSIM_RADIUS + 1));
+    let ma = sa / n;
+    let mb = sb / n;
+    let va = saa / n - ma * ma;
+    let vb = sbb / n - mb * mb;
+    let cov = sab / n - ma * mb;
+    return ((2.0 * ma * mb + SSIM_C1) * (2.0 * cov + SSIM_C2))
+        / ((ma * ma + mb * mb + SSIM_C1) * (va + vb + SSIM_C2));
+}
+
+// 1 - SSIM of the window around the pixel over black and over white, means / variances / covariance summed up in one pass
+fn ssim_error(grid: vec3<u32>, dimensions: vec2<u32>) -> f32 {
+    let last = vec2<i32>(dimensions) - vec2<i32>(1, 1);
+    var black = array<f32, 5>(0.0, 0.0, 0.0, 0.0, 0.0);
+    var white = array<f32, 5>(0.0, 0.0, 0.0, 0.0, 0.0);
+    for (var dy = -SSIM_RADIUS; dy <= SSIM_RADIUS; dy += 1) {
+        for (var dx = -SSIM_RADIUS; dx <= SSIM_RADIUS; dx += 1) {
+            let p = clamp(vec2<i32>(grid.xy) + vec2<i32>(dx, dy), vec2<i32>(0, 0), last);
+            let a = textureLoad(source, p, 0);
+            let b = textureLoad(current, p, i32(grid.z), 0);
+            let ab = luma(a.xyz * a.w);
+            let bb = luma(b.xyz);
+            let aw = luma(a.xyz * a.w + (1.0 - a.w));
+            let bw = luma(b.xyz + (1.0 - b.w));
+            black[0] += ab;
+            black[1] += bb;
+            black[2] += ab * ab;
+            black[3] += bb * bb;
+            black[4] += ab * bb;
+            white[0] += aw;
+            white[1] += bw;
+            white[2] += aw * aw;
+            white[3] += bw * bw;
+            white[4] += aw * bw;
+        }
+    }
+    let e_black = 1.0 - ssim(black[0], black[1], black[2], black[3], black[4]);
+    let e_white = 1.0 - ssim(white[0], white[1], white[2], white[3], white[4]);
+    return (e_black + e_white) * 0.5;
+}
+
+// a and b are normalized sRGB, already composited
+fn color_error(a: vec3<f32>, b: vec3<f32>) -> f32 {
+    if (params.metric == 1u) {
+        return distance(srgb_to_lab(a), srgb_to_lab(b));
+    } else if (params.metric == 2u) {
+        return delta_e2000(srgb_to_lab(a), srgb_to_lab(b));
+    }
+    let diff: vec3<f32> = (a - b) * 255.0;
+    return sqrt(dot(diff, diff));
+}
+
+// grid.z is the layer of 'current', i.e. which drawing of a batch
+// source is straight alpha, current premultiplied (see the BlendState), compared over black and
+// over white when either isn't opaque so differences in alpha count too
 @compute
 @workgroup_size(8, 8, 1)
 fn main(@builtin(global_invocation_id) grid: vec3<u32>) {
-    let a: vec4<f32> = textureLoad(source, grid.xy, 1);
-    let b: vec4<f32> = textureLoad(current, grid.xy, 1);
-    let diff: vec3<f32> = (a.xyz - b.xyz) * 255;
-    error[grid.y * textureDimensions(source).x + grid.x] = sqrt(dot(diff, diff));
-}
\ No newline at end of file
+    let dimensions = textureDimensions(source);
+    if (grid.x >= dimensions.x || grid.y >= dimensions.y) {
+        return; // the last row / column of workgroups sticks out when the size isn't a multiple of 8
+    }
+    let index = (grid.z * dimensions.y + grid.y) * dimensions.x + grid.x;
+    let rect = rects[grid.z];
+    if (any(grid.xy < rect.xy) || any(grid.xy >= rect.zw)) {
+        error[index] = 0.0; // the same as the reference, only rendered inside the rect
+        return;
+    }
+    let a: vec4<f32> = textureLoad(source, grid.xy, 0);
+    let b: vec4<f32> = textureLoad(current, grid.xy, grid.z, 0);
+    var e: f32;
+    if (params.metric == 3u) {
+        e = ssim_error(grid, dimensions);
+    } else {
+        let a_black = a.xyz * a.w;
+        let b_black = b.xyz;
+        e = color_error(a_black, b_black);
+        if (a.w < 1.0 || b.w < 1.0) {
+            e = (e + color_error(a_black + (1.0 - a.w), b_black + (1.0 - b.w))) * 0.5;
+        }
+    }
+    e *= textureLoad(mask, grid.xy, 0).x;
+    if (params.incremental == 1u) {
+        e -= reference[grid.y * dimensions.x + grid.x];
+    }
+    error[index] = e;
+}
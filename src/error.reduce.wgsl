@@ -0,0 +1,40 @@
+// sums up 'input' into one value per workgroup
+// run once over the per pixel error (one partial sum per workgroup) and once more with a single workgroup over the partial sums
+// 'input' is split into one equally sized segment per workgroup row (group.y), one for each drawing of a batch
+@binding(0) @group(0) var<storage, read> input: array<f32>;
+@binding(1) @group(0) var<storage, read_write> output: array<f32>;
+
+const WORKGROUP_SIZE: u32 = 256u;
+
+var<workgroup> partial: array<f32, WORKGROUP_SIZE>;
+
+@compute
+@workgroup_size(256, 1, 1)
+fn main(
+    @builtin(local_invocation_id) local: vec3<u32>,
+    @builtin(workgroup_id) group: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    // grid-stride loop, every thread sums every (groups * WORKGROUP_SIZE)th value
+    var sum: f32 = 0.0;
+    let stride = groups.x * WORKGROUP_SIZE;
+    let segment_length = arrayLength(&input) / groups.y;
+    let segment_start = group.y * segment_length;
+    for (var i = group.x * WORKGROUP_SIZE + local.x; i < segment_length; i = i + stride) {
+        sum = sum + input[segment_start + i];
+    }
+    partial[local.x] = sum;
+    workgroupBarrier();
+
+    // tree reduction in workgroup memory
+    for (var s = WORKGROUP_SIZE / 2u; s > 0u; s = s / 2u) {
+        if (local.x < s) {
+            partial[local.x] = partial[local.x] + partial[local.x + s];
+        }
+        workgroupBarrier();
+    }
+
+    if (local.x == 0u) {
+        output[group.y * groups.x + group.x] = partial[0];
+    }
+}
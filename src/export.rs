@@ -0,0 +1,167 @@
+use std::io::Cursor;
+
+use anyhow::{ensure, Result};
+use image::codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder};
+use image::{ColorType, ImageEncoder};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::backend::{AnyBackend, Backend};
+use crate::model::drawing::Drawing;
+use crate::util::unpremultiply;
+use crate::Vertex;
+
+// biggest render target used for one tile (supersampling included), keeps memory per tile bounded
+// and stays within max_texture_dimension_2d of Limits::downlevel_defaults
+const MAX_TILE_SIZE: usize = 2048;
+
+const JPEG_QUALITY: u8 = 90;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg, // no alpha channel, transparent drawings get composited over white
+    WebP, // lossless
+}
+
+impl ImageFormat {
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "webp" => Some(ImageFormat::WebP),
+            _ => None,
+        }
+    }
+}
+
+// renders the drawing at width x height, every pixel averaged from supersampling^2 samples
+// split into tiles so the size isn't limited by the backend's max texture size
+// samples are averaged premultiplied, the result is straight alpha like encode expects
+// a single pixel's samples have to fit in one tile, so supersampling is limited to the tile size
+pub(crate) async fn render(
+    backend: &mut AnyBackend,
+    drawing: &Drawing,
+    width: usize,
+    height: usize,
+    supersampling: usize,
+) -> Result<Vec<u8>> {
+    ensure!(
+        width > 0 && height > 0,
+        "can't export a {}x{} image",
+        width,
+        height
+    );
+    let max_tile_size = MAX_TILE_SIZE.min(backend.max_texture_size());
+    ensure!(
+        (1..=max_tile_size).contains(&supersampling),
+        "supersampling has to be between 1 and {}, got {}",
+        max_tile_size,
+        supersampling
+    );
+    let ss = supersampling;
+    let tile_size = max_tile_size / ss;
+    let vertices = drawing.to_vertices();
+
+    let mut pixels = vec![0u8; width * height * 4];
+    for y in (0..height).step_by(tile_size) {
+        for x in (0..width).step_by(tile_size) {
+            let tile_width = tile_size.min(width - x);
+            let tile_height = tile_size.min(height - y);
+
+            let tile_vertices = tile_vertices(
+                &vertices,
+                (width * ss, height * ss),
+                (x * ss, y * ss, tile_width * ss, tile_height * ss),
+            );
+            let tile = backend
+                .render_vertices(&tile_vertices, tile_width * ss, tile_height * ss)
+                .await;
+            let tile = downsample(&tile, tile_width, tile_height, ss);
+
+            for row in 0..tile_height {
+                let start = ((y + row) * width + x) * 4;
+                pixels[start..start + tile_width * 4]
+                    .copy_from_slice(&tile[row * tile_width * 4..(row + 1) * tile_width * 4]);
+            }
+        }
+    }
+    unpremultiply(&mut pixels);
+    Ok(pixels)
+}
+
+// unpadded RGBA8 pixels -> file contents
+pub fn encode(pixels: &[u8], width: usize, height: usize, format: ImageFormat) -> Result<Vec<u8>> {
+    let (w, h) = (width as u32, height as u32);
+    let mut bytes = Cursor::new(vec![]);
+    match format {
+        ImageFormat::Png => {
+            PngEncoder::new(&mut bytes).write_image(pixels, w, h, ColorType::Rgba8)?
+        }
+        ImageFormat::Jpeg => {
+            let rgb: Vec<u8> = pixels
+                .chunks_exact(4)
+                .flat_map(|p| {
+                    let a = p[3] as u32;
+                    [0, 1, 2].map(|i| ((p[i] as u32 * a + 255 * (255 - a) + 127) / 255) as u8)
+                })
+                .collect();
+            JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY).write_image(
+                &rgb,
+                w,
+                h,
+                ColorType::Rgb8,
+            )?
+        }
+        ImageFormat::WebP => {
+            WebPEncoder::new_lossless(&mut bytes).write_image(pixels, w, h, ColorType::Rgba8)?
+        }
+    }
+    Ok(bytes.into_inner())
+}
+
+// clip space of the full (width, height) image -> clip space of the (x, y, w, h) tile, all in pixels
+fn tile_vertices(
+    vertices: &[Vertex],
+    (width, height): (usize, usize),
+    (x, y, w, h): (usize, usize, usize, usize),
+) -> Vec<Vertex> {
+    let (width, height) = (width as f32, height as f32);
+    let (x, y, w, h) = (x as f32, y as f32, w as f32, h as f32);
+    vertices
+        .iter()
+        .map(|v| {
+            let px = (v.position[0] + 1.0) * 0.5 * width;
+            let py = (1.0 - v.position[1]) * 0.5 * height;
+            let mut position = v.position;
+            position[0] = (px - x) / w * 2.0 - 1.0;
+            position[1] = 1.0 - (py - y) / h * 2.0;
+            Vertex { position, ..*v }
+        })
+        .collect()
+}
+
+// box filter, every output pixel is the average of a factor x factor block
+fn downsample(pixels: &[u8], width: usize, height: usize, factor: usize) -> Vec<u8> {
+    if factor == 1 {
+        return pixels.to_vec();
+    }
+    let samples = (factor * factor) as u32;
+    let mut output = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 4];
+            for sy in 0..factor {
+                let row = (y * factor + sy) * width * factor;
+                for sx in 0..factor {
+                    let i = (row + x * factor + sx) * 4;
+                    for c in 0..4 {
+                        sum[c] += pixels[i + c] as u32;
+                    }
+                }
+            }
+            output.extend(sum.map(|s| ((s + samples / 2) / samples) as u8));
+        }
+    }
+    output
+}
@@ -13,6 +13,7 @@ import test from "./assets/test.json";
 // TODO: clean up imports
 
 import {
+  BackendKind,
   Engine,
   default as init
 } from "./assets/wasm/renderer";
@@ -46,6 +47,8 @@ export interface Stats {
   sessionDuration: number;
   cycleTime: number; // duration of the entire cycle
   ticks: number; // number of ticks during last cycle
+  seed?: number; // reported by the engine, pass it to Engine.new to replay a run
+  level?: number; // pyramid level being evolved, 0 = full resolution
 }
 
 // max for both dimensions -> will scale down maintaining aspect ratio
@@ -84,6 +87,8 @@ const updateUIstats = (stats: Stats) => {
       stats?.ticks || 0
     } ticks/cycle.`
   );
+  rows.push(`Seed: ${stats?.seed ?? "-"}`);
+  rows.push(`Level: ${stats?.level ?? "-"}`);
 
   requestAnimationFrame(
     () =>
@@ -244,7 +249,9 @@ const createEngine = async (dimensions: Dimensions): Promise<Engine> => {
   // const black = [0, 0, 0, 255];
   // const source_bytes = new Uint8Array(Array(w*h).fill(black).flat());
   const { w, h } = dimensions;
-  return Engine.new(source_bytes, best_drawing, w, h); // pass best_drawing instead of null normally, testing starting from scratch
+  // null mask -> every pixel counts the same, null settings -> defaults, or Settings.from_json(json), null seed -> random
+  // pass best_drawing instead of null normally, testing starting from scratch
+  return Engine.new(source_bytes, null, best_drawing, null, null, w, h, BackendKind.Auto);
 };
 
 // called before loadWasm to adjust UI and setup state
@@ -1,29 +1,80 @@
+use std::collections::VecDeque;
+use std::mem;
+
+use anyhow::{ensure, Context};
+use backend::{AnyBackend, Backend, Pending, Rect};
+use image::imageops::FilterType;
 use log::info;
 use model::drawing::Drawing;
+use model::error_map::ErrorMap;
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
-use std::mem::{self};
-use texture::Texture;
-use util::BufferDimensions;
 use wasm_bindgen::prelude::wasm_bindgen;
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{JsError, JsValue};
 use wasm_timer::Instant;
-use wgpu::{vertex_attr_array, BlendState};
 
-use crate::model::settings::{MAX_ERROR_PER_PIXEL, PER_POINT_MULTIPLIER};
-use crate::util::{calculate_error_from_gpu, draw_on_canvas_internal, get_bytes, Timer};
+use crate::model::color::Color;
+use crate::model::settings::Settings;
+use crate::pyramid::{scale, Level, Pyramid};
+use crate::util::{
+    draw_on_canvas_internal, mean_color, seeded_rng, to_js_error, unpremultiply, SeededRng, Timer,
+};
+pub use backend::BackendKind;
+pub use export::ImageFormat;
+pub use metric::ErrorMetric;
+pub mod backend;
 mod entrypoints;
-mod model;
+pub mod export;
+pub mod metric;
+pub mod model;
+mod pyramid;
+pub mod rasterizer;
 mod texture;
 mod util;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct Stats {
-    generated: usize,
-    improvements: usize,
-    cycle_time: usize,
-    ticks: usize,
+pub struct Stats {
+    pub generated: usize,
+    pub improvements: usize,
+    pub cycle_time: usize,
+    pub ticks: usize,
+    pub seed: u32, // replays the same mutations given the same image, starting drawing and settings
+    #[serde(default)]
+    pub level: usize, // resolution the engine is evolving at, 0 = full size, see Pyramid
+}
+
+// bump whenever the layout changes, old checkpoints are rejected instead of resuming with garbage
+const CHECKPOINT_VERSION: u32 = 1;
+
+// everything needed to resume a session exactly where it stopped
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checkpoint {
+    version: u32,
+    best_drawing: Drawing, // includes its fitness
+    stats: Stats,
+    settings: Settings,
+    rng: SeededRng,
+    #[serde(default)]
+    stagnation: usize, // so resuming moves up a pyramid level at the same point
+    #[serde(default)]
+    in_flight: Vec<ScoredGeneration>, // started but not compared yet, the RNG is already past their mutations
+}
+
+// a Generation that finished scoring, kept in a checkpoint
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScoredGeneration {
+    mutants: Vec<Drawing>,
+    rects: Option<Vec<Rect>>,
+    errors: Vec<f32>,
+}
+
+// mutants of what was the best drawing when the backend started scoring them, see Engine::step
+struct Generation {
+    mutants: Vec<Drawing>,
+    rects: Option<Vec<Rect>>, // when scored incrementally, what changed compared to that best drawing
+    pending: Pending,
 }
 
 #[repr(C)]
@@ -31,27 +82,28 @@ struct Stats {
 pub struct Vertex {
     position: [f32; 4],
     color: [f32; 4],
+    // ellipses: position relative to the radii in xy and 1 in z
+    // polygons: position in 0..1 in xy and the kind of fill in w (0 = solid, 1 = linear, 2 = radial gradient)
+    shape: [f32; 4],
+    gradient: [f32; 4],       // linear: start and end, radial: center and radius
+    gradient_color: [f32; 4], // the color the gradient ends in, it starts with color
 }
 
 #[wasm_bindgen()]
 pub struct Engine {
     width: usize,
     height: usize,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    buffer_dimensions: BufferDimensions,
-    drawing_output_buffer: wgpu::Buffer,
-    texture_extent: wgpu::Extent3d,
-    drawing_texture: wgpu::Texture,
-    render_pipeline: wgpu::RenderPipeline,
-    compute_bind_group: wgpu::BindGroup,
-    compute_pipeline: wgpu::ComputePipeline,
-    error_source_buffer: wgpu::Buffer,
-    error_output_buffer: wgpu::Buffer,
+    pyramid: Pyramid,
+    stagnation: usize, // mutations since the last improvement
+    backend: AnyBackend,
+    metric: ErrorMetric, // what the backend measures and best_drawing.fitness is based on, follows settings.error_metric
     running: bool,
     best_drawing: Drawing,
     best_drawing_bytes: Vec<u8>,
-    error_bytes: Vec<u8>,
+    error_map: Option<ErrorMap>, // of the best drawing, only kept with settings.error_guided_placement
+    in_flight: VecDeque<Generation>, // oldest first, dropped whenever the best drawing gets scored differently
+    settings: Settings,
+    rng: SeededRng,
     stats: Stats,
 }
 
@@ -61,389 +113,77 @@ impl Engine {
         self.running = !self.running;
     }
 
+    // mask_bytes: optional importance of every pixel, grayscale or RGBA8 (red channel) at width x height
+    #[allow(clippy::too_many_arguments)] // one per JS argument
     pub async fn new(
         source_bytes: Vec<u8>,
+        mask_bytes: Option<Vec<u8>>,
         best_drawing: JsValue,
+        settings: Option<Settings>,
+        seed: Option<u32>,
         width: usize,
         height: usize,
-    ) -> Self {
-        let running = false;
-
-        let backends = wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all);
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends,
-            dx12_shader_compiler: wgpu::Dx12Compiler::default(),
-        });
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions::default())
-            .await
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::downlevel_defaults(),
-                },
-                None,
-            )
-            .await
-            .unwrap();
-
-        // It is a WebGPU requirement that ImageCopyBuffer.layout.bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0
-        // So we calculate padded_bytes_per_row by rounding unpadded_bytes_per_row
-        // up to the next multiple of wgpu::COPY_BYTES_PER_ROW_ALIGNMENT.
-        // https://en.wikipedia.org/wiki/Data_structure_alignment#Computing_padding
-        let buffer_dimensions = BufferDimensions::new(width, height);
-        // The output buffer lets us retrieve the data as an array
-        let drawing_output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            size: (buffer_dimensions.padded_bytes_per_row * buffer_dimensions.height) as u64,
-            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        // will copy this to error_output_buffer after the compute pass
-        let error_source_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            size: (buffer_dimensions.width * buffer_dimensions.height * 4) as u64,
-            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::STORAGE,
-            mapped_at_creation: false,
-        });
-
-        // final error output per pixel
-        let error_output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: None,
-            size: (buffer_dimensions.width * buffer_dimensions.height * 4) as u64,
-            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let texture_extent = wgpu::Extent3d {
-            width: buffer_dimensions.width as u32,
-            height: buffer_dimensions.height as u32,
-            depth_or_array_layers: 1,
-        };
-
-        let texture_format = wgpu::TextureFormat::Rgba8Unorm;
-
-        // The render pipeline renders data into this texture
-        let drawing_texture = device.create_texture(&wgpu::TextureDescriptor {
-            size: texture_extent,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: texture_format,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::COPY_SRC
-                | wgpu::TextureUsages::TEXTURE_BINDING,
-            label: None,
-            view_formats: &[],
-        });
-        let view = drawing_texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let compute_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0, // 'source' bytes loaded from target image
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Texture {
-                            multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1, // 'current' render target for drawing
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Texture {
-                            multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 2, // 'error' output <-- error=sqrt((re*re)+(ge*ge)+(be*be)) in RGBA32Float
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: wgpu::BufferSize::new(
-                                (buffer_dimensions.width * buffer_dimensions.height * 4) as u64,
-                            ),
-                        },
-                        count: None,
-                    },
-                ],
-                label: Some("compute_bind_group_layout"),
-            });
-
-        // doubt this makes much sense, attempting to pass in the dimensions as a 1x2 R32Uint texture
-        let dimensions = (width as u32, height as u32);
-        // let dimensions_texture = Texture::from_dimensions(&device, &queue, dimensions).unwrap();
-
-        let source_texture = Texture::from_bytes(
-            &device,
-            &queue,
-            &source_bytes.as_slice(),
-            dimensions,
-            &"source",
-        )
-        .unwrap();
-
-        let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &compute_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    // source image texture WxH Rgba8Unorm
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&source_texture.view),
-                },
-                wgpu::BindGroupEntry {
-                    // render target texture WxH Rgba8Unorm
-                    binding: 1,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    // error calc output texture WxH Rgba8Unorm
-                    binding: 2,
-                    resource: wgpu::BindingResource::Buffer(
-                        error_source_buffer.as_entire_buffer_binding(),
-                    ),
-                },
-            ],
-            label: Some("compute_bind_group"),
-        });
-
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: None,
-                bind_group_layouts: &[],
-                push_constant_ranges: &[],
-            });
-
-        // Load the shaders from disk
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
-        });
-
-        let vertex_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: (mem::size_of::<f32>() * 8) as u64,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &vertex_attr_array![0 => Float32x4, 1 => Float32x4],
-        };
-
-        let mut primitive = wgpu::PrimitiveState::default();
-        primitive.cull_mode = None;
-
-        let blend_state: BlendState = BlendState {
-            color: wgpu::BlendComponent {
-                src_factor: wgpu::BlendFactor::SrcAlpha,
-                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                operation: wgpu::BlendOperation::Add,
-            },
-            alpha: wgpu::BlendComponent {
-                src_factor: wgpu::BlendFactor::One,
-                dst_factor: wgpu::BlendFactor::One,
-                operation: wgpu::BlendOperation::Max,
-            },
-        };
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[vertex_buffer_layout],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: texture_format,
-                    blend: Some(blend_state),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: primitive,
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-        });
-
-        let compute_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("compute_pipeline_layout"),
-                bind_group_layouts: &[&compute_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-
-        let compute_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("error.compute2.wgsl"))),
-        });
-
-        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-            label: None,
-            layout: Some(&compute_pipeline_layout),
-            module: &compute_module,
-            entry_point: "main",
-        });
-
-        let best_drawing: Drawing = match best_drawing.is_falsy() {
-            true => Drawing::new_random(),
-            false => Drawing::from(best_drawing),
+        backend: BackendKind,
+    ) -> Result<Engine, JsError> {
+        let best_drawing = match best_drawing.is_falsy() {
+            true => None,
+            false => Some(Drawing::try_from(best_drawing).map_err(to_js_error)?),
         };
-
-        let best_drawing_bytes: Vec<u8> = vec![]; // can only set after drawing in post_init
-
-        Engine {
+        Engine::from_drawing(
+            source_bytes,
+            mask_bytes,
+            best_drawing,
+            settings.unwrap_or_default(),
+            seed,
             width,
             height,
-            device,
-            queue,
-            buffer_dimensions,
-            drawing_output_buffer,
-            texture_extent,
-            drawing_texture,
-            render_pipeline,
-            compute_bind_group,
-            compute_pipeline,
-            error_source_buffer,
-            error_output_buffer,
-            running,
-            best_drawing,
-            best_drawing_bytes,
-            error_bytes: vec![0; source_bytes.len()],
-            stats: Stats {
-                generated: 0,
-                improvements: 0,
-                cycle_time: 0,
-                ticks: 0,
-            },
-        }
+            backend,
+        )
+        .await
+        .map_err(to_js_error)
     }
 
-    async fn draw(&self, drawing: &Drawing) {
-        let vertices: Vec<Vertex> = drawing.to_vertices();
-
-        // create buffer, write buffer (bytemuck?)
-        let vertex_buffer = wgpu::util::DeviceExt::create_buffer_init(
-            &self.device,
-            &wgpu::util::BufferInitDescriptor {
-                label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(&vertices),
-                usage: wgpu::BufferUsages::VERTEX,
-            },
-        );
-
-        let command_buffer: wgpu::CommandBuffer = {
-            let mut encoder = self
-                .device
-                .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-
-            let view = &self
-                .drawing_texture
-                .create_view(&wgpu::TextureViewDescriptor::default());
-
-            // Set the background to be white
-            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::WHITE), // WHY DOES DRAWING WHITE TRIANGLES ON TOP OF THIS DO ANYTHING?
-                        store: true,
-                    },
-                })],
-                depth_stencil_attachment: None,
-            });
-
-            rpass.set_pipeline(&self.render_pipeline);
-            // rpass.set_bind_group(0, &self.bind_group, &[]);
-            rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
-            rpass.draw(0..vertices.len() as u32, 0..vertices.len() as u32);
-
-            // encoder methods like begin_render_pass and copy_texture_to_buffer take a &'pass mut self
-            // drop rpass before copy_texture_to_buffer to avoid: cannot borrow `encoder` as mutable more than once at a time
-            drop(rpass);
-
-            // Copy the data from the texture to the buffer
-            encoder.copy_texture_to_buffer(
-                self.drawing_texture.as_image_copy(),
-                wgpu::ImageCopyBuffer {
-                    buffer: &self.drawing_output_buffer,
-                    layout: wgpu::ImageDataLayout {
-                        offset: 0,
-                        bytes_per_row: Some(self.buffer_dimensions.padded_bytes_per_row as u32),
-                        rows_per_image: None,
-                    },
-                },
-                self.texture_extent,
-            );
-
-            encoder.finish()
-        };
-
-        self.queue.submit(Some(command_buffer));
+    #[wasm_bindgen(getter)]
+    pub fn settings(&self) -> Settings {
+        self.settings
     }
 
-    async fn calculate_error(&self, width: u32, height: u32) -> wgpu::SubmissionIndex {
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("calculate_error_command_encoder"),
-            });
-
-        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-            label: Some("Compute Pass"),
-        });
-        cpass.set_pipeline(&self.compute_pipeline);
-        cpass.set_bind_group(0, &self.compute_bind_group, &[]);
-        cpass.dispatch_workgroups(width / 8, height / 8, 1); // compute shader workgroup_size is (8, 8, 1)
-        drop(cpass);
-
-        encoder.copy_buffer_to_buffer(
-            &self.error_source_buffer,
-            0,
-            &self.error_output_buffer,
-            0,
-            (width * height * 4) as u64,
-        );
+    // takes effect from the next generation on, throws and keeps the current ones if they don't validate
+    #[wasm_bindgen(setter)]
+    pub fn set_settings(&mut self, settings: Settings) -> Result<(), JsError> {
+        settings.validate().map_err(to_js_error)?;
+        self.settings = settings;
+        Ok(())
+    }
 
-        self.queue.submit(Some(encoder.finish()))
+    // weighs the error of every pixel, see Engine::new, None = all pixels count the same
+    // throws and keeps the current mask if it's the wrong size or all black
+    pub async fn set_mask(&mut self, mask_bytes: Option<Vec<u8>>) -> Result<(), JsError> {
+        self.pyramid.set_mask(mask_bytes).map_err(to_js_error)?;
+        let level = self.pyramid.level(self.level());
+        self.backend.set_mask(level.mask.as_deref());
+        self.evaluate_best().await; // weighted differently now
+        Ok(())
     }
 
-    // FIXME: would like this to take &mut self so we can:
-    // self.best_drawing_bytes = get_bytes(&self.drawing_output_buffer).await;
-    // self.error_bytes = get_bytes(&self.error_output_buffer).await; // prevent having to fetch again later
-    // self.best_drawing.fitness = fitness; // store it after calculating
-    // the problem is if it takes &mut self it's not usable from other functions like post_init, tick etc
-    async fn evaluate_drawing(&self, drawing: &Drawing) -> (f32, f32, Vec<u8>, Vec<u8>) {
-        // step 1 - render pipeline --> draw our triangles to a texture
-        self.draw(&drawing).await;
-        let best_drawing_bytes = get_bytes(&self.drawing_output_buffer).await; //
+    // only the total error gets read back, pixels and heatmap stay on the backend until we ask for them
+    async fn evaluate_drawing(&mut self, drawing: &Drawing) -> (f32, f32) {
+        // step 1 - render the drawing (render pipeline, rasterizer or canvas depending on the backend)
+        self.backend.render(drawing).await;
 
-        // Step 2 - compute pipeline --> diff drawing texture vs source texture
-        self.calculate_error(self.width as u32, self.height as u32)
-            .await;
+        // Step 2 - diff drawing vs source and sum up the error
+        let error = self.backend.score().await;
+        (error, self.fitness(drawing, error))
+    }
 
-        // Step 3 - calculate error and error heatmap (sum output of compute pipeline)
-        // TODO: parallel reduction on GPU, something like https://eximia.co/implementing-parallel-reduction-in-cuda/
-        let error_buffer = get_bytes(&self.error_output_buffer).await;
-        let (error, error_heatmap) = calculate_error_from_gpu(&error_buffer);
-        let max_total_error: f32 = MAX_ERROR_PER_PIXEL * self.width as f32 * self.height as f32;
+    fn fitness(&self, drawing: &Drawing, error: f32) -> f32 {
+        // with a mask the pixels only count as much as their weight
+        let max_total_error: f32 =
+            self.metric.max_error_per_pixel() * self.pyramid.level(self.level()).weight;
         let mut fitness: f32 = 100.0 * (1.0 - error / max_total_error);
-        let penalty = fitness * PER_POINT_MULTIPLIER * drawing.num_points() as f32;
+        let penalty = fitness * self.settings.per_point_multiplier * drawing.num_points() as f32;
         fitness -= penalty;
-        (error, fitness, best_drawing_bytes, error_heatmap)
+        fitness
     }
 
     async fn mutate_new_best(&mut self, mut drawing: Drawing) -> Drawing {
@@ -456,7 +196,7 @@ impl Engine {
             c1 = 0;
             while !drawing.is_dirty {
                 // it's possible it won't be mutated at all since all mutations have low probability
-                drawing.mutate();
+                drawing.mutate(&self.settings, self.error_map.as_ref(), &mut self.rng);
                 c1 += 1; // for one mutation
                 c2 += 1; // total
                 if c1 >= 100 && c1 % 100 == 0 {
@@ -475,28 +215,38 @@ impl Engine {
     }
 
     pub async fn post_init(&mut self) {
-        let (error, fitness, best_drawing_bytes, error_heatmap) =
-            self.evaluate_drawing(&self.best_drawing).await;
+        self.evaluate_best().await;
 
-        self.display_to_canvas(&best_drawing_bytes, "wgpu-canvas")
+        self.display_to_canvas(&self.best_drawing_bytes, "wgpu-canvas")
             .await;
+        let error_heatmap = self.backend.error_heatmap().await;
         self.display_to_canvas(&error_heatmap, "error-canvas").await;
-
-        self.best_drawing.fitness = fitness;
-        self.best_drawing_bytes = best_drawing_bytes;
-        self.error_bytes = error_heatmap;
-
-        log::info!("post_init done, error = {}, fitness = {}", error, fitness);
     }
 
-    async fn display_to_canvas(&self, bytes: &Vec<u8>, canvas_id: &str) {
-        draw_on_canvas_internal(&bytes, &canvas_id).await;
+    async fn display_to_canvas(&self, bytes: &[u8], canvas_id: &str) {
+        // canvases want straight alpha, renders are premultiplied
+        let mut bytes = bytes.to_vec();
+        unpremultiply(&mut bytes);
+        // the canvases are always full size, lower levels get stretched to fit
+        let full_size = self.pyramid.level(0);
+        if self.width != full_size.width || self.height != full_size.height {
+            bytes = scale(
+                &bytes,
+                self.width,
+                self.height,
+                full_size.width,
+                full_size.height,
+                FilterType::Nearest,
+            );
+        }
+        draw_on_canvas_internal(&bytes, canvas_id).await;
     }
 
     pub async fn tick(&mut self, max_time_ms: usize, canvas_id: &str) -> JsValue {
         self.stats.ticks = 0;
         let mut elapsed: usize = 0;
         let display_best = canvas_id.len() > 0;
+        let mut improved = false;
         while elapsed < max_time_ms {
             let _timer: Timer; // scope determines lifetime (time_end on destruction) -> can't be inside the if statement
             if model::settings::DEBUG_TIMERS {
@@ -505,28 +255,23 @@ impl Engine {
             self.stats.ticks += 1;
             let t0 = Instant::now();
 
-            let mut clone = self.best_drawing.clone();
-            clone.mutate();
-            self.stats.generated += 1;
-            let (_error, fitness, best_drawing_bytes, error_heatmap) =
-                self.evaluate_drawing(&clone).await;
-            clone.fitness = fitness;
-            if clone.fitness > self.best_drawing.fitness {
-                if display_best {
-                    // TODO: don't await here?
-                    self.display_to_canvas(&best_drawing_bytes, &canvas_id)
-                        .await;
-                    self.display_to_canvas(&error_heatmap, "error-canvas") // TODO: pass in error_canvas_id
-                        .await;
-                }
-
-                self.best_drawing = clone;
-                self.best_drawing_bytes = best_drawing_bytes;
-                self.stats.improvements += 1;
-            }
+            improved |= self.step().await;
             elapsed += t0.elapsed().as_millis() as usize;
         }
 
+        // once per tick, reading the heatmap back waits for every generation in flight
+        // those stay in flight in between ticks so the GPU has something to do while JS runs
+        if improved && display_best {
+            self.display_to_canvas(&self.best_drawing_bytes, &canvas_id)
+                .await;
+            // the backend's last score is of a mutant, the heatmap should be the best drawing's
+            let best_drawing = self.best_drawing.clone();
+            self.evaluate_drawing(&best_drawing).await;
+            let error_heatmap = self.backend.error_heatmap().await;
+            self.display_to_canvas(&error_heatmap, "error-canvas") // TODO: pass in error_canvas_id
+                .await;
+        }
+
         self.stats.cycle_time = elapsed; // can't get f64 ms directly
         return JsValue::from(serde_json::to_string(&self.stats).expect("Expected valid stats."));
     }
@@ -535,4 +280,550 @@ impl Engine {
         self.stats.generated = 0;
         self.stats.improvements = 0;
     }
+
+    // for exporting at the original image size rather than the (scaled down) working size
+    pub fn best_drawing_svg(&self, width: usize, height: usize) -> String {
+        self.best_drawing.to_svg(width, height, true)
+    }
+
+    // async fns can only hand a JsValue back to JS, see the native export_image, throws like it fails
+    #[wasm_bindgen(js_name = export_image)]
+    pub async fn export_image_js(
+        &mut self,
+        width: usize,
+        height: usize,
+        supersampling: usize,
+        format: ImageFormat,
+    ) -> Result<js_sys::Uint8Array, JsError> {
+        let bytes = self
+            .export_image(width, height, supersampling, format)
+            .await
+            .map_err(to_js_error)?;
+        Ok(js_sys::Uint8Array::from(bytes.as_slice()))
+    }
+
+    // resolves to the json of the native save_checkpoint once the generations in flight are scored
+    #[wasm_bindgen(js_name = save_checkpoint)]
+    pub async fn save_checkpoint_js(&mut self) -> String {
+        self.save_checkpoint().await
+    }
+
+    // continues from a save_checkpoint, throws if it can't be read (the engine is left as it was)
+    #[wasm_bindgen(js_name = load_checkpoint)]
+    pub async fn load_checkpoint_js(&mut self, checkpoint: String) -> Result<(), JsError> {
+        self.load_checkpoint(&checkpoint).await.map_err(to_js_error)
+    }
+}
+
+// not exported to JS, these don't depend on a browser so they can also be driven natively (see src/bin)
+impl Engine {
+    // an error for settings or a best_drawing that don't validate or a source or mask that doesn't
+    // match width x height
+    #[allow(clippy::too_many_arguments)] // same as Engine::new
+    pub async fn from_drawing(
+        source_bytes: Vec<u8>,
+        mask: Option<Vec<u8>>,
+        best_drawing: Option<Drawing>,
+        settings: Settings,
+        seed: Option<u32>,
+        width: usize,
+        height: usize,
+        backend: BackendKind,
+    ) -> anyhow::Result<Self> {
+        settings.validate()?;
+        if let Some(drawing) = &best_drawing {
+            drawing.validate().context("invalid best_drawing")?;
+        }
+        let running = false;
+
+        // start at the coarsest level, step() moves up when it stagnates
+        let pyramid = Pyramid::new(source_bytes, mask, width, height, settings.pyramid_levels)?;
+        let level = pyramid.coarsest();
+        let Level {
+            width,
+            height,
+            source_bytes,
+            mask,
+            ..
+        } = pyramid.level(level);
+        let (width, height) = (*width, *height);
+
+        // no seed given -> pick one, it's reported in the stats so the run can still be replayed
+        let seed = seed.unwrap_or_else(rand::random);
+        let mut rng = seeded_rng(seed);
+        log::info!("seed = {}", seed);
+
+        let mut backend = AnyBackend::new(
+            backend,
+            source_bytes,
+            width,
+            height,
+            settings.batch_size,
+            Drawing::max_vertices(&settings),
+        )
+        .await;
+        backend.set_metric(settings.error_metric);
+        backend.set_mask(mask.as_deref());
+
+        let best_drawing = best_drawing.unwrap_or_else(|| {
+            // dark targets converge much faster on a dark background than on white
+            let background = match settings.transparent_background {
+                true => Color::TRANSPARENT,
+                false => mean_color(source_bytes),
+            };
+            Drawing::new_random(background, &settings, &mut rng)
+        });
+
+        let best_drawing_bytes: Vec<u8> = vec![]; // can only set after drawing in post_init
+
+        Ok(Engine {
+            width,
+            height,
+            pyramid,
+            stagnation: 0,
+            backend,
+            metric: settings.error_metric,
+            running,
+            best_drawing,
+            best_drawing_bytes,
+            error_map: None, // can only set after scoring in post_init
+            in_flight: VecDeque::new(),
+            settings,
+            rng,
+            stats: Stats {
+                generated: 0,
+                improvements: 0,
+                cycle_time: 0,
+                ticks: 0,
+                seed,
+                level,
+            },
+        })
+    }
+
+    // evaluates the starting drawing so we have a fitness to beat and bytes to display
+    pub async fn evaluate_best(&mut self) -> f32 {
+        self.in_flight.clear(); // would be compared against a different fitness
+        let best_drawing = self.best_drawing.clone();
+        let (error, fitness) = self.evaluate_drawing(&best_drawing).await;
+
+        self.best_drawing.fitness = fitness;
+        self.best_drawing_bytes = self.backend.drawing_bytes().await;
+        self.update_error_map().await;
+        self.backend.set_reference();
+
+        log::info!("post_init done, error = {}, fitness = {}", error, fitness);
+        fitness
+    }
+
+    // json with the best drawing, stats, settings, the RNG state and the generations in flight
+    // those get finished first, but not compared yet, so resuming carries on exactly like an uninterrupted run
+    pub async fn save_checkpoint(&mut self) -> String {
+        let mut in_flight = Vec::with_capacity(self.in_flight.len());
+        for generation in &mut self.in_flight {
+            let pending = mem::replace(&mut generation.pending, Pending::Ready(vec![]));
+            let errors = self.backend.finish_batch(pending).await;
+            in_flight.push(ScoredGeneration {
+                mutants: generation.mutants.clone(),
+                rects: generation.rects.clone(),
+                errors: errors.clone(),
+            });
+            generation.pending = Pending::Ready(errors);
+        }
+        let checkpoint = Checkpoint {
+            version: CHECKPOINT_VERSION,
+            best_drawing: self.best_drawing.clone(),
+            stats: self.stats.clone(),
+            settings: self.settings,
+            rng: self.rng.clone(),
+            stagnation: self.stagnation,
+            in_flight,
+        };
+        serde_json::to_string(&checkpoint).expect("Expected serializable Checkpoint.")
+    }
+
+    // continues from a save_checkpoint, the source image and dimensions have to be the same
+    pub async fn load_checkpoint(&mut self, checkpoint: &str) -> anyhow::Result<()> {
+        // version first, other versions can fail to deserialize for less helpful reasons
+        let json: serde_json::Value =
+            serde_json::from_str(checkpoint).context("invalid checkpoint json")?;
+        ensure!(
+            json["version"] == CHECKPOINT_VERSION,
+            "unsupported checkpoint version {}, expected {}",
+            json["version"],
+            CHECKPOINT_VERSION
+        );
+        let checkpoint: Checkpoint = serde_json::from_value(json).context("invalid checkpoint")?;
+        checkpoint.settings.validate()?;
+        for generation in &checkpoint.in_flight {
+            ensure!(
+                !generation.mutants.is_empty()
+                    && generation.errors.len() == generation.mutants.len(),
+                "generation in flight without an error for every mutant"
+            );
+            for mutant in &generation.mutants {
+                mutant.validate().context("invalid mutant in flight")?;
+            }
+        }
+        checkpoint
+            .best_drawing
+            .validate()
+            .context("invalid best_drawing")?;
+
+        let fitness = checkpoint.best_drawing.fitness;
+        self.best_drawing = checkpoint.best_drawing;
+        self.stats = checkpoint.stats;
+        self.settings = checkpoint.settings;
+        self.rng = checkpoint.rng;
+        self.set_level(self.stats.level.min(self.pyramid.coarsest()));
+        self.stagnation = checkpoint.stagnation;
+        self.metric = self.settings.error_metric;
+        self.backend.set_metric(self.metric);
+
+        // need the pixels for display anyway, this also catches resuming against a different image
+        if self.evaluate_best().await != fitness {
+            log::warn!(
+                "checkpoint fitness was {}, now {} (different image or backend?)",
+                fitness,
+                self.best_drawing.fitness
+            );
+        }
+        self.in_flight = checkpoint
+            .in_flight
+            .into_iter()
+            .map(|generation| Generation {
+                mutants: generation.mutants,
+                rects: generation.rects,
+                pending: Pending::Ready(generation.errors),
+            })
+            .collect();
+        Ok(())
+    }
+
+    // a single generation: mutate batch_size clones of the best drawing and keep the fittest if it scores higher
+    // the GPU scores the next generation while the last one is read back, so what gets compared is the oldest
+    // of pipeline_depth generations in flight, mutants of what was the best drawing when it was started
+    pub async fn step(&mut self) -> bool {
+        if self.metric != self.settings.error_metric {
+            // best_drawing.fitness was measured differently, mutants would be compared against the wrong number
+            self.metric = self.settings.error_metric;
+            self.backend.set_metric(self.metric);
+            self.evaluate_best().await;
+        }
+        if self.settings.error_guided_placement != self.error_map.is_some() {
+            // toggled, the backend's last score is of a mutant so the best has to be scored again
+            self.evaluate_best().await;
+        }
+        self.start_generation().await;
+        if self.in_flight.len() < self.pipeline_depth() {
+            return false;
+        }
+        let generation = self
+            .in_flight
+            .pop_front()
+            .expect("Expected a generation in flight.");
+        self.finish_generation(generation).await
+    }
+
+    // finishes and compares every generation in flight, e.g. before stopping so every generated mutant gets scored
+    pub async fn drain(&mut self) -> bool {
+        let mut improved = false;
+        while let Some(generation) = self.in_flight.pop_front() {
+            improved |= self.finish_generation(generation).await;
+        }
+        improved
+    }
+
+    // waits for the generation's errors and keeps its fittest mutant if that beats the best drawing
+    async fn finish_generation(&mut self, generation: Generation) -> bool {
+        let Generation {
+            mut mutants,
+            rects,
+            pending,
+        } = generation;
+        let errors = self.backend.finish_batch(pending).await;
+        if let (Some(rects), true) = (&rects, model::settings::DEBUG_INCREMENTAL_EVALUATION) {
+            let full = self.backend.score_batch(&mutants).await;
+            for ((error, full), rect) in errors.iter().zip(full).zip(rects) {
+                // summed up in a different order, only equal up to rounding
+                assert!(
+                    (error - full).abs() <= full.abs() * 1e-5 + 0.01,
+                    "incremental error {} vs full {} in {:?}",
+                    error,
+                    full,
+                    rect
+                );
+            }
+        }
+        for (mutant, error) in mutants.iter_mut().zip(errors) {
+            mutant.fitness = self.fitness(mutant, error);
+        }
+        let batch_size = mutants.len();
+        let mut best = mutants
+            .into_iter()
+            .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+            .expect("Expected at least one mutant.");
+        // after a single full render with nothing started since the backend still holds the new best,
+        // otherwise render it again to read the pixels back
+        // an incremental score is only as good as the reference, so the new one gets its fitness from a full evaluation
+        // and is only kept if that still beats the best
+        if best.fitness > self.best_drawing.fitness
+            && (batch_size > 1 || rects.is_some() || !self.in_flight.is_empty())
+        {
+            best.fitness = self.evaluate_drawing(&best).await.1;
+        }
+        if best.fitness <= self.best_drawing.fitness {
+            self.stagnation += batch_size;
+            if self.level() > 0 && self.stagnation >= self.settings.level_up_stagnation {
+                // best_drawing_bytes changed, same as an improvement as far as displaying goes
+                self.set_level(self.level() - 1);
+                self.evaluate_best().await;
+                return true;
+            }
+            return false;
+        }
+        self.stagnation = 0;
+        self.best_drawing = best;
+        self.best_drawing_bytes = self.backend.drawing_bytes().await;
+        self.update_error_map().await;
+        self.backend.set_reference();
+        self.stats.improvements += 1;
+        true
+    }
+
+    // mutates batch_size clones of the best drawing and hands them to the backend without waiting for their errors
+    async fn start_generation(&mut self) {
+        let batch_size = self.settings.batch_size;
+        let mutants: Vec<Drawing> = (0..batch_size)
+            .map(|_| {
+                let mut clone = self.best_drawing.clone();
+                clone.mutate(&self.settings, self.error_map.as_ref(), &mut self.rng);
+                clone
+            })
+            .collect();
+        self.stats.generated += mutants.len();
+
+        // Ssim's windows spread a change beyond its bounds, it always gets scored in full
+        let incremental = self.settings.incremental_evaluation && self.metric != ErrorMetric::Ssim;
+        let rects: Option<Vec<Rect>> =
+            incremental.then(|| mutants.iter().map(|m| self.dirty_rect(m)).collect());
+        let pending = self.backend.start_batch(&mutants, rects.as_deref()).await;
+        self.in_flight.push_back(Generation {
+            mutants,
+            rects,
+            pending,
+        });
+    }
+
+    // only the GPU gets anything done while we wait for a generation, the others score it in start_batch
+    fn pipeline_depth(&self) -> usize {
+        match self.backend.kind() {
+            BackendKind::Gpu => self.settings.pipeline_depth,
+            _ => 1,
+        }
+    }
+
+    // pixels where the mutant can look different from the best drawing
+    fn dirty_rect(&self, mutant: &Drawing) -> Rect {
+        match self.best_drawing.changed_bounds(mutant) {
+            Some((min, max)) => Rect::covering(min, max, self.width, self.height),
+            None => Rect::EMPTY,
+        }
+    }
+
+    // reads back the per pixel error of the last score, which has to be the best drawing's
+    async fn update_error_map(&mut self) {
+        if !self.settings.error_guided_placement {
+            self.error_map = None;
+            return;
+        }
+        let errors = self.backend.errors().await;
+        let source_bytes = self.pyramid.level(self.level()).source_bytes.clone();
+        self.error_map = Some(ErrorMap::new(errors, source_bytes, self.width, self.height));
+    }
+
+    // best drawing at any size (independent of the working size), encoded as png, jpeg or webp
+    // supersampling = n renders n x n samples per pixel, 1 = no anti-aliasing like the engine itself
+    // an error for an empty size or more supersampling than fits in one tile
+    pub async fn export_image(
+        &mut self,
+        width: usize,
+        height: usize,
+        supersampling: usize,
+        format: ImageFormat,
+    ) -> anyhow::Result<Vec<u8>> {
+        let pixels = export::render(
+            &mut self.backend,
+            &self.best_drawing,
+            width,
+            height,
+            supersampling,
+        )
+        .await?;
+        export::encode(&pixels, width, height, format)
+    }
+
+    // switches the source image and backend to another level of the pyramid
+    // the drawing carries over as is since its coordinates don't depend on the size, call evaluate_best afterwards
+    fn set_level(&mut self, level: usize) {
+        let Level {
+            width,
+            height,
+            source_bytes,
+            mask,
+            ..
+        } = self.pyramid.level(level);
+        self.in_flight.clear(); // scored at the old size
+        self.backend.set_source(source_bytes, *width, *height);
+        self.backend.set_mask(mask.as_deref());
+        self.width = *width;
+        self.height = *height;
+        self.stats.level = level;
+        self.stagnation = 0;
+        log::info!("level {} ({}x{})", level, width, height);
+    }
+
+    pub fn level(&self) -> usize {
+        self.stats.level
+    }
+
+    pub fn best_drawing(&self) -> &Drawing {
+        &self.best_drawing
+    }
+
+    // premultiplied RGBA8 pixels of the best drawing, see util::unpremultiply
+    pub fn best_drawing_pixels(&self) -> &[u8] {
+        &self.best_drawing_bytes
+    }
+
+    pub fn backend(&self) -> BackendKind {
+        self.backend.kind()
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pollster::block_on;
+
+    use super::*;
+
+    // engine on four flat quadrants, cheap enough for a few hundred generations
+    // and far enough from the starting background that mutants keep improving on it
+    fn engine(seed: u32) -> Engine {
+        engine_on(seed, BackendKind::Cpu)
+    }
+
+    fn engine_on(seed: u32, backend: BackendKind) -> Engine {
+        let (width, height) = (16, 16);
+        let source: Vec<u8> = (0..width * height)
+            .flat_map(|i| match (i % width < width / 2, i / width < height / 2) {
+                (true, true) => [0, 0, 0, 255],
+                (false, false) => [255, 255, 255, 255],
+                _ => [255, 0, 0, 255],
+            })
+            .collect();
+        let mut engine = block_on(Engine::from_drawing(
+            source,
+            None,
+            None,
+            Settings::default(),
+            Some(seed),
+            width,
+            height,
+            backend,
+        ))
+        .unwrap();
+        block_on(engine.evaluate_best());
+        engine
+    }
+
+    fn json(drawing: &Drawing) -> String {
+        serde_json::to_string(drawing).unwrap()
+    }
+
+    #[test]
+    fn resuming_a_checkpoint_is_exact() {
+        let mut engine1 = engine(7);
+        for _ in 0..100 {
+            block_on(engine1.step());
+        }
+        let checkpoint = block_on(engine1.save_checkpoint());
+        for _ in 0..100 {
+            block_on(engine1.step());
+        }
+
+        let mut engine2 = engine(1);
+        block_on(engine2.load_checkpoint(&checkpoint)).unwrap();
+        for _ in 0..100 {
+            block_on(engine2.step());
+        }
+        assert_eq!(json(engine1.best_drawing()), json(engine2.best_drawing()));
+    }
+
+    // only the GPU keeps generations in flight, they're carried over in the checkpoint
+    // which is taken while the first improving generation is one of them
+    #[test]
+    fn resuming_with_generations_in_flight_is_exact() {
+        let mut engine = engine_on(7, BackendKind::Auto);
+        if engine.backend.kind() != BackendKind::Gpu {
+            return; // no adapter to test with
+        }
+        let start = block_on(engine.save_checkpoint());
+        let mut steps = 1;
+        while !block_on(engine.step()) {
+            steps += 1;
+        }
+
+        block_on(engine.load_checkpoint(&start)).unwrap();
+        for _ in 0..steps - 1 {
+            block_on(engine.step());
+        }
+        let checkpoint = block_on(engine.save_checkpoint());
+        for _ in 0..10 {
+            block_on(engine.step());
+        }
+        let uninterrupted = json(engine.best_drawing());
+
+        block_on(engine.load_checkpoint(&checkpoint)).unwrap();
+        for _ in 0..10 {
+            block_on(engine.step());
+        }
+        assert_eq!(json(engine.best_drawing()), uninterrupted);
+    }
+
+    #[test]
+    fn bad_exports_are_errors() {
+        let mut engine = engine(1);
+        let png = block_on(engine.export_image(4, 4, 2, ImageFormat::Png));
+        assert!(!png.unwrap().is_empty());
+        assert!(block_on(engine.export_image(0, 4, 1, ImageFormat::Png)).is_err());
+        assert!(block_on(engine.export_image(4, 0, 1, ImageFormat::Png)).is_err());
+        assert!(block_on(engine.export_image(4, 4, 0, ImageFormat::Png)).is_err());
+        assert!(block_on(engine.export_image(4, 4, 1 << 20, ImageFormat::Png)).is_err());
+    }
+
+    #[test]
+    fn bad_checkpoints_are_errors() {
+        let mut engine = engine(1);
+        let best = json(engine.best_drawing());
+        let checkpoint = block_on(engine.save_checkpoint());
+
+        assert!(block_on(engine.load_checkpoint("not json")).is_err());
+        let other_version = checkpoint.replace("\"version\":1", "\"version\":99");
+        assert!(block_on(engine.load_checkpoint(&other_version)).is_err());
+        let bad_settings = checkpoint.replace("\"minAlpha\":", "\"minAlpha\":255,\"x\":");
+        assert!(block_on(engine.load_checkpoint(&bad_settings)).is_err());
+        let bad_stroke = checkpoint.replace("\"shapes\":[", "\"shapes\":[{\"points\":[],\"width\":0.1,\"color\":{\"r\":0,\"g\":0,\"b\":0,\"a\":1}},");
+        let error = block_on(engine.load_checkpoint(&bad_stroke)).unwrap_err();
+        assert!(
+            format!("{:#}", error).contains("a stroke has 3 or 4 points"),
+            "{:#}",
+            error
+        );
+        assert_eq!(json(engine.best_drawing()), best);
+    }
 }
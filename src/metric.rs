@@ -0,0 +1,403 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::settings::MAX_ERROR_PER_PIXEL;
+
+// D65 reference white, what sRGB is defined against
+const WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+// SSIM looks at a (2 * radius + 1)^2 window around every pixel, 7x7 like scikit-image's default
+const SSIM_RADIUS: isize = 3;
+
+// stabilize the SSIM divisions for flat dark windows, (0.01 * L)^2 and (0.03 * L)^2 with L = 1
+const SSIM_C1: f32 = 0.0001;
+const SSIM_C2: f32 = 0.0009;
+
+// how far apart a source and a drawing pixel are, error.compute2.wgsl does the same math on the GPU
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorMetric {
+    #[default]
+    Rgb, // euclidean distance of the 0..255 RGB values, cheapest but over-weights differences we barely see
+    DeltaE76,   // euclidean distance in CIELAB
+    DeltaE2000, // CIEDE2000, also corrects CIELAB for saturated colors, blues and grays
+    Ssim, // 1 - structural similarity of the luma around every pixel, rewards edges and texture instead of blurry averages
+}
+
+impl ErrorMetric {
+    // largest error a single pixel can have, normalizes the total error into a fitness
+    // the CIELAB ones are (just above) the furthest apart two sRGB colors get, found by brute force
+    // ΔE76: pure blue vs pure green, ΔE2000: ~119.5 between dark blue and a yellowish green
+    pub fn max_error_per_pixel(self) -> f32 {
+        match self {
+            ErrorMetric::Rgb => MAX_ERROR_PER_PIXEL,
+            ErrorMetric::DeltaE76 => 258.7,
+            ErrorMetric::DeltaE2000 => 120.0,
+            ErrorMetric::Ssim => 2.0, // SSIM is in -1..1
+        }
+    }
+
+    // source is straight alpha RGBA8, drawing premultiplied RGBA8 (what the render pipeline outputs)
+    // compared over black and, when either isn't opaque, also over white so differences in alpha count too
+    pub fn pixel_error(self, source: &[u8], drawing: &[u8]) -> f32 {
+        let (source_black, source_white) = over_black_and_white(source, false);
+        let (drawing_black, drawing_white) = over_black_and_white(drawing, true);
+        let error = self.color_error(source_black, drawing_black);
+        if source[3] == 255 && drawing[3] == 255 {
+            return error;
+        }
+        (error + self.color_error(source_white, drawing_white)) * 0.5
+    }
+
+    // a and b are normalized (0..1) sRGB like textureLoad gives us in the shader
+    fn color_error(self, a: [f32; 3], b: [f32; 3]) -> f32 {
+        match self {
+            ErrorMetric::Rgb => {
+                let re = (a[0] - b[0]) * 255.0;
+                let ge = (a[1] - b[1]) * 255.0;
+                let be = (a[2] - b[2]) * 255.0;
+                f32::sqrt(re * re + ge * ge + be * be)
+            }
+            ErrorMetric::DeltaE76 => delta_e76(srgb_to_lab(a), srgb_to_lab(b)),
+            ErrorMetric::DeltaE2000 => delta_e2000(srgb_to_lab(a), srgb_to_lab(b)),
+            ErrorMetric::Ssim => {
+                unreachable!("SSIM needs the neighbouring pixels, see ssim_errors")
+            }
+        }
+    }
+}
+
+// normalized colors of an RGBA8 pixel composited over black and over white, both are the pixel itself when it's opaque
+// same operations as the shader (textureLoad gives us normalized floats) to get the same rounding
+fn over_black_and_white(p: &[u8], premultiplied: bool) -> ([f32; 3], [f32; 3]) {
+    let alpha = p[3] as f32 / 255.0;
+    let rgb = [
+        p[0] as f32 / 255.0,
+        p[1] as f32 / 255.0,
+        p[2] as f32 / 255.0,
+    ];
+    let black = match premultiplied {
+        true => rgb,
+        false => rgb.map(|c| c * alpha),
+    };
+    (black, black.map(|c| c + (1.0 - alpha)))
+}
+
+// BT.601 luma of normalized sRGB, SSIM is usually computed on the gamma encoded values
+fn luma(rgb: [f32; 3]) -> f32 {
+    rgb[0] * 0.299 + rgb[1] * 0.587 + rgb[2] * 0.114
+}
+
+// 1 - SSIM for every pixel of two unpadded RGBA8 images (see pixel_error for the alpha handling)
+// windows get clamped to the edges of the image, same loops (and summation order) as error.compute2.wgsl
+pub fn ssim_errors(source_bytes: &[u8], drawing_bytes: &[u8], width: usize) -> Vec<f32> {
+    let lumas = |bytes: &[u8], premultiplied: bool| -> (Vec<f32>, Vec<f32>) {
+        bytes
+            .chunks_exact(4)
+            .map(|p| {
+                let (black, white) = over_black_and_white(p, premultiplied);
+                (luma(black), luma(white))
+            })
+            .unzip()
+    };
+    let (a_black, a_white) = lumas(source_bytes, false);
+    let (b_black, b_white) = lumas(drawing_bytes, true);
+    let height = a_black.len() / width;
+
+    let mut errors = Vec::with_capacity(a_black.len());
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let black = 1.0 - window_ssim(&a_black, &b_black, width, height, x, y);
+            let white = 1.0 - window_ssim(&a_white, &b_white, width, height, x, y);
+            errors.push((black + white) * 0.5);
+        }
+    }
+    errors
+}
+
+fn window_ssim(a: &[f32], b: &[f32], width: usize, height: usize, x: isize, y: isize) -> f32 {
+    let n = ((2 * SSIM_RADIUS + 1) * (2 * SSIM_RADIUS + 1)) as f32;
+    let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for dy in -SSIM_RADIUS..=SSIM_RADIUS {
+        for dx in -SSIM_RADIUS..=SSIM_RADIUS {
+            let px = (x + dx).clamp(0, width as isize - 1) as usize;
+            let py = (y + dy).clamp(0, height as isize - 1) as usize;
+            let (va, vb) = (a[py * width + px], b[py * width + px]);
+            sa += va;
+            sb += vb;
+            saa += va * va;
+            sbb += vb * vb;
+            sab += va * vb;
+        }
+    }
+    ssim(sa / n, sb / n, saa / n, sbb / n, sab / n)
+}
+
+// from the window means of a, b, a^2, b^2 and a * b
+fn ssim(ma: f32, mb: f32, maa: f32, mbb: f32, mab: f32) -> f32 {
+    let (va, vb, cov) = (maa - ma * ma, mbb - mb * mb, mab - ma * mb);
+    ((2.0 * ma * mb + SSIM_C1) * (2.0 * cov + SSIM_C2))
+        / ((ma * ma + mb * mb + SSIM_C1) * (va + vb + SSIM_C2))
+}
+
+// sRGB transfer function -> linear light
+fn decode(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn srgb_to_lab(rgb: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(decode);
+    let xyz = [
+        0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+        0.2126729 * r + 0.7151522 * g + 0.0721750 * b,
+        0.0193339 * r + 0.119192 * g + 0.9503041 * b,
+    ];
+    let [fx, fy, fz] = [0, 1, 2].map(|i| {
+        let t = xyz[i] / WHITE[i];
+        // linear segment near black, (6/29)^3 and 1 / (3 * (6/29)^2)
+        if t > 0.008856452 {
+            t.cbrt()
+        } else {
+            t * 7.787037 + 4.0 / 29.0
+        }
+    });
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+pub fn delta_e76(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let (dl, da, db) = (a[0] - b[0], a[1] - b[1], a[2] - b[2]);
+    f32::sqrt(dl * dl + da * da + db * db)
+}
+
+// Sharma, Wu, Dalal: "The CIEDE2000 Color-Difference Formula: Implementation Notes", kL = kC = kH = 1
+pub fn delta_e2000(lab1: [f32; 3], lab2: [f32; 3]) -> f32 {
+    let [l1, a1, b1] = lab1;
+    let [l2, a2, b2] = lab2;
+    let pow25_7 = 6103515625.0f32; // 25^7
+
+    let c_bar = (f32::hypot(a1, b1) + f32::hypot(a2, b2)) * 0.5;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - f32::sqrt(c_bar7 / (c_bar7 + pow25_7)));
+    let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+    let (c1, c2) = (f32::hypot(a1, b1), f32::hypot(a2, b2));
+    let hue = |b: f32, a: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dh = 2.0 * f32::sqrt(c1 * c2) * (dh * 0.5).to_radians().sin();
+
+    let l_bar = (l1 + l2) * 0.5;
+    let c_bar = (c1 + c2) * 0.5;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) * 0.5
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) * 0.5
+    } else {
+        (h1 + h2 - 360.0) * 0.5
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * f32::exp(-((h_bar - 275.0) / 25.0).powi(2));
+    let c_bar7 = c_bar.powi(7);
+    let rc = 2.0 * f32::sqrt(c_bar7 / (c_bar7 + pow25_7));
+    let l50 = (l_bar - 50.0) * (l_bar - 50.0);
+    let sl = 1.0 + 0.015 * l50 / f32::sqrt(20.0 + l50);
+    let sc = 1.0 + 0.045 * c_bar;
+    let sh = 1.0 + 0.015 * c_bar * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    let (l, c, h) = (dl / sl, dc / sc, dh / sh);
+    f32::sqrt((l * l + c * c + h * h + rt * c * h).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sharma, Wu, Dalal table 1: L, a, b of both samples and the expected ΔE2000
+    const SHARMA: [([f32; 3], [f32; 3], f32); 34] = [
+        ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+        ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+        ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+        ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+        ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+        ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+        ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+        ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+        ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+        ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+        ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+        (
+            [60.2574, -34.0099, 36.2677],
+            [60.4626, -34.1751, 39.4387],
+            1.2644,
+        ),
+        (
+            [63.0109, -31.0961, -5.8663],
+            [62.8187, -29.7946, -4.0864],
+            1.2630,
+        ),
+        (
+            [61.2901, 3.7196, -5.3901],
+            [61.4292, 2.2480, -4.9620],
+            1.8731,
+        ),
+        (
+            [35.0831, -44.1164, 3.7933],
+            [35.0232, -40.0716, 1.5901],
+            1.8645,
+        ),
+        (
+            [22.7233, 20.0904, -46.6940],
+            [23.0331, 14.9730, -42.5619],
+            2.0373,
+        ),
+        (
+            [36.4612, 47.8580, 18.3852],
+            [36.2715, 50.5065, 21.2231],
+            1.4146,
+        ),
+        (
+            [90.8027, -2.0831, 1.4410],
+            [91.1528, -1.6435, 0.0447],
+            1.4441,
+        ),
+        (
+            [90.9257, -0.5406, -0.9208],
+            [88.6381, -0.8985, -0.7239],
+            1.5381,
+        ),
+        (
+            [6.7747, -0.2908, -2.4247],
+            [5.8714, -0.0985, -2.2286],
+            0.6377,
+        ),
+        (
+            [2.0776, 0.0795, -1.1350],
+            [0.9033, -0.0636, -0.5514],
+            0.9082,
+        ),
+    ];
+
+    #[test]
+    fn delta_e2000_matches_sharma() {
+        for (i, (a, b, expected)) in SHARMA.iter().enumerate() {
+            let actual = delta_e2000(*a, *b);
+            assert!(
+                (actual - expected).abs() < 1e-3,
+                "pair {}: {} instead of {}",
+                i + 1,
+                actual,
+                expected
+            );
+            // symmetric
+            assert!((delta_e2000(*b, *a) - actual).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn srgb_to_lab_matches_reference() {
+        let cases = [
+            ([1.0, 1.0, 1.0], [100.0, 0.0, 0.0]),
+            ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0]),
+            ([1.0, 0.0, 0.0], [53.2408, 80.0925, 67.2032]),
+            ([0.0, 1.0, 0.0], [87.7347, -86.1827, 83.1793]),
+            ([0.0, 0.0, 1.0], [32.2970, 79.1875, -107.8602]),
+            ([0.5, 0.5, 0.5], [53.3889, 0.0, 0.0]),
+        ];
+        for (rgb, expected) in cases {
+            let lab = srgb_to_lab(rgb);
+            for c in 0..3 {
+                assert!(
+                    (lab[c] - expected[c]).abs() < 0.01,
+                    "{:?}: {:?} instead of {:?}",
+                    rgb,
+                    lab,
+                    expected
+                );
+            }
+        }
+    }
+
+    // every pair on a grid of every 15th value per channel (both ends included), 8.5M pairs
+    #[test]
+    fn no_srgb_pair_exceeds_the_max_error() {
+        let steps: Vec<f32> = (0..=255).step_by(15).map(|v| v as f32 / 255.0).collect();
+        let mut labs = vec![];
+        for &r in &steps {
+            for &g in &steps {
+                for &b in &steps {
+                    labs.push(srgb_to_lab([r, g, b]));
+                }
+            }
+        }
+        let (mut max76, mut max2000) = (0.0f32, 0.0f32);
+        for (i, &a) in labs.iter().enumerate() {
+            for &b in &labs[i + 1..] {
+                max76 = max76.max(delta_e76(a, b));
+                max2000 = max2000.max(delta_e2000(a, b));
+            }
+        }
+        assert!(
+            max76 <= ErrorMetric::DeltaE76.max_error_per_pixel(),
+            "{}",
+            max76
+        );
+        assert!(
+            max2000 <= ErrorMetric::DeltaE2000.max_error_per_pixel(),
+            "{}",
+            max2000
+        );
+        // and not so loose that fitness loses its range
+        assert!(
+            max76 > ErrorMetric::DeltaE76.max_error_per_pixel() * 0.99,
+            "{}",
+            max76
+        );
+        assert!(
+            max2000 > ErrorMetric::DeltaE2000.max_error_per_pixel() * 0.95,
+            "{}",
+            max2000
+        );
+    }
+}
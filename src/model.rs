@@ -1,5 +1,11 @@
 pub mod color;
 pub mod drawing;
+pub mod ellipse;
+pub mod error_map;
+pub mod fill;
 pub mod point;
 pub mod polygon;
-pub mod settings;
\ No newline at end of file
+pub mod settings;
+pub mod shape;
+pub mod stroke;
+pub mod svg;
\ No newline at end of file
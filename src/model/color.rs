@@ -1,12 +1,11 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use super::settings::{
-    CHANGE_COLOR_PROB, DARKEN_COLOR_PROB, LIGHTEN_COLOR_PROB, MAX_ALPHA,
-    MICRO_ADJUSTMENT_PROBABILITY, MIN_ALPHA,
-};
+use crate::util::SeededRng;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+use super::settings::Settings;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -15,54 +14,76 @@ pub struct Color {
 }
 
 impl Color {
-    pub fn new_random() -> Color {
+    // background of drawings from before it was stored in them
+    pub const WHITE: Color = Color {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+
+    pub const TRANSPARENT: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
+
+    pub fn new_random(settings: &Settings, rng: &mut SeededRng) -> Color {
         Color {
-            r: rand::thread_rng().gen::<u8>(),
-            g: rand::thread_rng().gen::<u8>(),
-            b: rand::thread_rng().gen::<u8>(),
-            a: rand::thread_rng().gen::<u8>().clamp(MIN_ALPHA, MAX_ALPHA),
+            r: rng.gen::<u8>(),
+            g: rng.gen::<u8>(),
+            b: rng.gen::<u8>(),
+            a: Color::random_alpha(settings, rng),
         }
     }
 
-    pub fn mutate(&mut self) -> bool {
+    pub fn random_alpha(settings: &Settings, rng: &mut SeededRng) -> u8 {
+        rng.gen::<u8>()
+            .clamp(settings.min_alpha, settings.max_alpha)
+    }
+
+    pub fn mutate(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
         let mut mutation_happened = false;
 
-        if rand::thread_rng().gen::<f32>() < CHANGE_COLOR_PROB {
-            self.r = rand::thread_rng().gen::<u8>();
+        if rng.gen::<f32>() < settings.change_color_prob {
+            self.r = rng.gen::<u8>();
             mutation_happened = true;
         }
-        if rand::thread_rng().gen::<f32>() < CHANGE_COLOR_PROB {
-            self.g = rand::thread_rng().gen::<u8>();
+        if rng.gen::<f32>() < settings.change_color_prob {
+            self.g = rng.gen::<u8>();
             mutation_happened = true;
         }
-        if rand::thread_rng().gen::<f32>() < CHANGE_COLOR_PROB {
-            self.b = rand::thread_rng().gen::<u8>();
+        if rng.gen::<f32>() < settings.change_color_prob {
+            self.b = rng.gen::<u8>();
             mutation_happened = true;
         }
-        if rand::thread_rng().gen::<f32>() < CHANGE_COLOR_PROB {
-            self.a = rand::thread_rng().gen::<u8>().clamp(MIN_ALPHA, MAX_ALPHA);
+        if rng.gen::<f32>() < settings.change_color_prob {
+            self.a = rng
+                .gen::<u8>()
+                .clamp(settings.min_alpha, settings.max_alpha);
             mutation_happened = true;
         }
 
         //// same but micro adjustments
-        if rand::thread_rng().gen::<f32>() < MICRO_ADJUSTMENT_PROBABILITY {
-            self.r = Color::micro_adjust(self.r);
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            self.r = Color::micro_adjust(self.r, rng);
             mutation_happened = true;
         }
-        if rand::thread_rng().gen::<f32>() < MICRO_ADJUSTMENT_PROBABILITY {
-            self.g = Color::micro_adjust(self.g);
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            self.g = Color::micro_adjust(self.g, rng);
             mutation_happened = true;
         }
-        if rand::thread_rng().gen::<f32>() < MICRO_ADJUSTMENT_PROBABILITY {
-            self.b = Color::micro_adjust(self.b);
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            self.b = Color::micro_adjust(self.b, rng);
             mutation_happened = true;
         }
-        if rand::thread_rng().gen::<f32>() < MICRO_ADJUSTMENT_PROBABILITY {
-            self.a = Color::micro_adjust(self.a).clamp(MIN_ALPHA, MAX_ALPHA);
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            self.a = Color::micro_adjust(self.a, rng).clamp(settings.min_alpha, settings.max_alpha);
             mutation_happened = true;
         }
         ////
-        if rand::thread_rng().gen::<f32>() < LIGHTEN_COLOR_PROB {
+        if rng.gen::<f32>() < settings.lighten_color_prob {
             if self.r < u8::MAX && self.g < u8::MAX && self.b < u8::MAX {
                 self.r += 1;
                 self.g += 1;
@@ -70,7 +91,7 @@ impl Color {
                 mutation_happened = true;
             }
         }
-        if rand::thread_rng().gen::<f32>() < DARKEN_COLOR_PROB {
+        if rng.gen::<f32>() < settings.darken_color_prob {
             if self.r > u8::MIN && self.g > u8::MIN && self.b > u8::MIN {
                 self.r -= 1;
                 self.g -= 1;
@@ -83,8 +104,8 @@ impl Color {
     }
 
     // increment or decrement with 50% chance while avoiding overflows and underflows
-    fn micro_adjust(mut val: u8) -> u8 {
-        val = if rand::thread_rng().gen::<f32>() > 0.5 {
+    fn micro_adjust(mut val: u8, rng: &mut SeededRng) -> u8 {
+        val = if rng.gen::<f32>() > 0.5 {
             if val < u8::MAX {
                 val + 1
             } else {
@@ -1,24 +1,44 @@
+use std::f64::consts::TAU;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{Context, Error, Result};
+use bytemuck::Zeroable;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsValue;
 use web_sys::CanvasRenderingContext2d;
 
-use crate::{util::Timer, Vertex};
+use crate::{
+    util::{SeededRng, Timer},
+    Vertex,
+};
 
 use super::{
-    polygon::Polygon,
-    settings::{
-        ADD_POLYGON_PROB, DEBUG_TIMERS, MAX_POLYGONS_PER_IMAGE, MIN_POLYGONS_PER_IMAGE,
-        REMOVE_POLYGON_PROB, REORDER_POLYGON_PROB, START_WITH_POLYGONS_PER_IMAGE,
-    },
+    color::Color,
+    ellipse::Ellipse,
+    error_map::ErrorMap,
+    fill::Fill,
+    point::{bounds, Point},
+    polygon::{Polygon, Triangulation},
+    settings::{Settings, DEBUG_TIMERS},
+    shape::Shape,
+    stroke::Stroke,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Drawing {
-    pub polygons: Vec<Polygon>,
+    #[serde(alias = "polygons")] // drawings from before there were ellipses
+    pub shapes: Vec<Shape>,
     pub is_dirty: bool,
     pub fitness: f32,
+    #[serde(default = "default_background")]
+    pub background: Color, // underneath all shapes, alpha 0 for targets with an alpha channel (logos etc.)
+}
+
+fn default_background() -> Color {
+    Color::WHITE
 }
 
 impl Drawing {
@@ -31,31 +51,127 @@ impl Drawing {
         let w = ctx.canvas().unwrap().width() as f64;
         let h = ctx.canvas().unwrap().height() as f64;
 
-        ctx.set_fill_style(&JsValue::from("#fff"));
-        ctx.fill_rect(0.0, 0.0, w as f64, h as f64);
+        ctx.clear_rect(0.0, 0.0, w, h);
+        if self.background.a > 0 {
+            ctx.set_fill_style(&JsValue::from(css_color(&self.background)));
+            ctx.fill_rect(0.0, 0.0, w, h);
+        }
 
-        /* draw the polygons */
-        for polygon in &self.polygons {
-            /* Draw the starting vertex */
+        for shape in &self.shapes {
             ctx.begin_path();
-            ctx.move_to(
-                polygon.points[0].x as f64 * w,
-                polygon.points[0].y as f64 * h,
-            );
+            match shape {
+                Shape::Polygon(polygon) => {
+                    /* Draw the starting vertex */
+                    ctx.move_to(
+                        polygon.points[0].x as f64 * w,
+                        polygon.points[0].y as f64 * h,
+                    );
 
-            /* Create the rest of the vertices sequentially */
-            for i in 0..polygon.points.len() {
-                ctx.line_to(
-                    polygon.points[i].x as f64 * w,
-                    polygon.points[i].y as f64 * h,
-                );
+                    /* Create the rest of the vertices sequentially */
+                    for i in 0..polygon.points.len() {
+                        ctx.line_to(
+                            polygon.points[i].x as f64 * w,
+                            polygon.points[i].y as f64 * h,
+                        );
+                    }
+                }
+                Shape::Ellipse(ellipse) => {
+                    // in 0..1 coordinates and then stretched to the canvas, the same as to_vertices
+                    ctx.save();
+                    ctx.scale(w, h).unwrap();
+                    ctx.ellipse(
+                        ellipse.center.x as f64,
+                        ellipse.center.y as f64,
+                        ellipse.rx as f64,
+                        ellipse.ry as f64,
+                        ellipse.rotation as f64,
+                        0.0,
+                        TAU,
+                    )
+                    .unwrap();
+                    ctx.restore(); // the path keeps the transform it was built with
+                }
+                Shape::Stroke(stroke) => {
+                    // unlike the path, the line width depends on the transform when stroke() gets called
+                    // so it stays scaled until then, stretched the same way to_vertices does
+                    let p = &stroke.points;
+                    ctx.save();
+                    ctx.scale(w, h).unwrap();
+                    ctx.move_to(p[0].x as f64, p[0].y as f64);
+                    match p.len() {
+                        3 => ctx.quadratic_curve_to(
+                            p[1].x as f64,
+                            p[1].y as f64,
+                            p[2].x as f64,
+                            p[2].y as f64,
+                        ),
+                        _ => ctx.bezier_curve_to(
+                            p[1].x as f64,
+                            p[1].y as f64,
+                            p[2].x as f64,
+                            p[2].y as f64,
+                            p[3].x as f64,
+                            p[3].y as f64,
+                        ),
+                    }
+                    ctx.set_line_width(stroke.width as f64);
+                    ctx.set_stroke_style(&JsValue::from(css_color(&stroke.color)));
+                    ctx.stroke();
+                    ctx.restore();
+                    continue;
+                }
             }
             ctx.close_path();
 
-            let c = &polygon.color;
-            let color = format!("rgba({},{},{},{})", c.r, c.g, c.b, c.a as f32 / 255.0);
-            ctx.set_fill_style(&JsValue::from(color));
-            ctx.fill();
+            match shape {
+                Shape::Polygon(Polygon {
+                    fill: fill @ (Fill::Linear { .. } | Fill::Radial { .. }),
+                    color,
+                    ..
+                }) => {
+                    // gradients are in the coordinates of the transform when fill() gets called,
+                    // the path is in pixels already and stays where it is
+                    ctx.save();
+                    ctx.scale(w, h).unwrap();
+                    let (gradient, to) = match fill {
+                        Fill::Linear { start, end, color } => (
+                            ctx.create_linear_gradient(
+                                start.x as f64,
+                                start.y as f64,
+                                end.x as f64,
+                                end.y as f64,
+                            ),
+                            color,
+                        ),
+                        Fill::Radial {
+                            center,
+                            radius,
+                            color,
+                        } => (
+                            ctx.create_radial_gradient(
+                                center.x as f64,
+                                center.y as f64,
+                                0.0,
+                                center.x as f64,
+                                center.y as f64,
+                                *radius as f64,
+                            )
+                            .unwrap(),
+                            color,
+                        ),
+                        Fill::Solid => unreachable!(),
+                    };
+                    gradient.add_color_stop(0.0, &css_color(color)).unwrap();
+                    gradient.add_color_stop(1.0, &css_color(to)).unwrap();
+                    ctx.set_fill_style(&gradient);
+                    ctx.fill();
+                    ctx.restore();
+                }
+                _ => {
+                    ctx.set_fill_style(&JsValue::from(css_color(shape.color())));
+                    ctx.fill();
+                }
+            }
         }
 
         // get_image_data is very slow so we want to avoid it whenever possible
@@ -71,44 +187,185 @@ impl Drawing {
         );
     }
 
+    // one <polygon> / <ellipse> / <path> per Shape in z-order, scaled from 0..1 to width x height
+    // with_background = false leaves out the background <rect> so the svg can be put on top of something else
+    pub fn to_svg(&self, width: usize, height: usize, with_background: bool) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = width,
+            h = height
+        );
+        if with_background && self.background.a > 0 {
+            let c = &self.background;
+            writeln!(
+                svg,
+                "<rect width=\"{}\" height=\"{}\" fill=\"rgb({},{},{})\" fill-opacity=\"{}\"/>",
+                width,
+                height,
+                c.r,
+                c.g,
+                c.b,
+                c.a as f32 / 255.0
+            )
+            .unwrap();
+        }
+        for (i, shape) in self.shapes.iter().enumerate() {
+            let fill = svg_paint("fill", shape.color());
+            match shape {
+                Shape::Polygon(polygon) => {
+                    let points: Vec<String> = polygon
+                        .points
+                        .iter()
+                        .map(|p| format!("{},{}", p.x * width as f32, p.y * height as f32))
+                        .collect();
+                    let fill = match svg_gradient(polygon, i, width, height) {
+                        Some(gradient) => {
+                            writeln!(svg, "<defs>{}</defs>", gradient).unwrap();
+                            format!("fill=\"url(#g{})\"", i)
+                        }
+                        None => fill,
+                    };
+                    writeln!(svg, "<polygon points=\"{}\" {}/>", points.join(" "), fill).unwrap();
+                }
+                Shape::Ellipse(e) => {
+                    // stays in 0..1 and gets stretched by the transform, a rotated ellipse on a non-square image
+                    // isn't an axis aligned <ellipse> with a rotate() anymore
+                    writeln!(
+                        svg,
+                        "<ellipse transform=\"scale({} {}) rotate({} {} {})\" cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" {}/>",
+                        width,
+                        height,
+                        e.rotation.to_degrees(),
+                        e.center.x,
+                        e.center.y,
+                        e.center.x,
+                        e.center.y,
+                        e.rx,
+                        e.ry,
+                        fill
+                    )
+                    .unwrap();
+                }
+                Shape::Stroke(stroke) => {
+                    // stretched by the transform like ellipses, which scales the stroke-width too
+                    let p = &stroke.points;
+                    let command = if p.len() == 3 { "Q" } else { "C" };
+                    let points: Vec<String> =
+                        p[1..].iter().map(|p| format!("{} {}", p.x, p.y)).collect();
+                    writeln!(
+                        svg,
+                        "<path transform=\"scale({} {})\" d=\"M {} {} {} {}\" fill=\"none\" stroke-width=\"{}\" {}/>",
+                        width,
+                        height,
+                        p[0].x,
+                        p[0].y,
+                        command,
+                        points.join(" "),
+                        stroke.width,
+                        svg_paint("stroke", &stroke.color)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
     pub fn num_points(&self) -> usize {
-        self.polygons
+        self.shapes
             .iter()
-            .fold(0, |sum, polygon| sum + polygon.num_points())
+            .fold(0, |sum, shape| sum + shape.num_points())
+    }
+
+    // (min, max) of where other can look different from self, None when it looks exactly the same
+    // anything that moved between the two counts, a different background changes everything
+    pub fn changed_bounds(&self, other: &Drawing) -> Option<(Point, Point)> {
+        if self.background != other.background {
+            return Some((Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 1.0 }));
+        }
+        let (a, b) = (&self.shapes, &other.shapes);
+        let changed: Vec<&Shape> = if a.len() == b.len() {
+            // mutated in place or swapped, the shapes in between still get drawn the same way
+            a.iter()
+                .zip(b)
+                .filter(|(a, b)| a != b)
+                .flat_map(|(a, b)| [a, b])
+                .collect()
+        } else {
+            // added or removed, everything after the first difference moved up or down
+            let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+            let suffix = a
+                .iter()
+                .rev()
+                .zip(b.iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count()
+                .min(a.len().min(b.len()) - prefix);
+            a[prefix..a.len() - suffix]
+                .iter()
+                .chain(&b[prefix..b.len() - suffix])
+                .collect()
+        };
+        changed
+            .into_iter()
+            .map(Shape::bounds)
+            .reduce(|(min, max), (shape_min, shape_max)| bounds(&[min, max, shape_min, shape_max]))
     }
 
-    pub fn new_random() -> Drawing {
+    // an error for shapes the rest of the code can't handle, see FromStr
+    pub fn validate(&self) -> Result<()> {
+        for (i, shape) in self.shapes.iter().enumerate() {
+            if let Shape::Stroke(stroke) = shape {
+                stroke.validate().with_context(|| format!("shape {}", i))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn new_random(background: Color, settings: &Settings, rng: &mut SeededRng) -> Drawing {
         Drawing {
-            polygons: (0..START_WITH_POLYGONS_PER_IMAGE)
-                .map(|_| Polygon::new_random())
+            shapes: (0..settings.start_with_polygons_per_image)
+                .map(|_| Shape::new_random(settings, None, rng))
                 .collect(),
             is_dirty: true,
             fitness: 0.0,
+            background,
         }
     }
 
-    pub fn mutate(&mut self) {
-        if rand::thread_rng().gen::<f32>() < ADD_POLYGON_PROB {
-            if self.add_polygon() {
+    // error_map: where the drawing is off the most, None places new shapes and moves points uniformly
+    pub fn mutate(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) {
+        if rng.gen::<f32>() < settings.add_polygon_prob {
+            if self.add_shape(settings, error_map, rng) {
                 self.is_dirty = true;
             }
         }
 
-        if rand::thread_rng().gen::<f32>() < REMOVE_POLYGON_PROB {
-            if self.remove_polygon() {
+        if rng.gen::<f32>() < settings.remove_polygon_prob {
+            if self.remove_shape(settings, rng) {
                 self.is_dirty = true;
             }
         }
 
-        if rand::thread_rng().gen::<f32>() < REORDER_POLYGON_PROB {
-            if self.reorder_polygons() {
+        if rng.gen::<f32>() < settings.reorder_polygon_prob {
+            if self.reorder_shapes(rng) {
                 self.is_dirty = true;
             }
         }
 
+        if settings.mutate_background && self.mutate_background(settings, rng) {
+            self.is_dirty = true;
+        }
+
         let mut internal_mutation_happened = false;
-        self.polygons.iter_mut().for_each(|p| {
-            internal_mutation_happened = p.mutate();
+        self.shapes.iter_mut().for_each(|p| {
+            internal_mutation_happened = p.mutate(settings, error_map, rng);
         });
 
         if internal_mutation_happened {
@@ -116,147 +373,264 @@ impl Drawing {
         }
     }
 
-    pub fn add_polygon(&mut self) -> bool {
-        if self.polygons.len() >= MAX_POLYGONS_PER_IMAGE {
+    pub fn add_shape(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> bool {
+        if self.shapes.len() >= settings.max_polygons_per_image {
             return false;
         }
-        let polygon = Polygon::new_random();
-        let index = rand::thread_rng().gen_range(0..self.polygons.len() - 1);
-        self.polygons.insert(index, polygon);
+        let shape = Shape::new_random(settings, error_map, rng);
+        let index = rng.gen_range(0..=self.shapes.len());
+        self.shapes.insert(index, shape);
         return true;
     }
 
-    pub fn remove_polygon(&mut self) -> bool {
-        if self.polygons.len() < 1 {
+    pub fn remove_shape(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
+        if self.shapes.len() < 1 {
             return false;
         }
-        if self.polygons.len() <= MIN_POLYGONS_PER_IMAGE {
+        if self.shapes.len() <= settings.min_polygons_per_image {
             return false;
         }
-        let index = rand::thread_rng().gen_range(0..self.polygons.len() - 1);
-        self.polygons.remove(index);
+        let index = rng.gen_range(0..self.shapes.len());
+        self.shapes.remove(index);
         return true;
     }
 
-    pub fn reorder_polygons(&mut self) -> bool {
-        let l = self.polygons.len();
-        if self.polygons.len() < 2 {
+    pub fn reorder_shapes(&mut self, rng: &mut SeededRng) -> bool {
+        let l = self.shapes.len();
+        if self.shapes.len() < 2 {
             return false;
         }
-        let i1 = rand::thread_rng().gen_range(0..l - 1);
-        let mut i2 = rand::thread_rng().gen_range(0..l - 1);
+        let i1 = rng.gen_range(0..l);
+        let mut i2 = rng.gen_range(0..l);
         while i1 == i2 {
-            i2 = rand::thread_rng().gen_range(0..l - 1);
+            i2 = rng.gen_range(0..l);
         }
-        self.polygons.swap(i1, i2);
+        self.shapes.swap(i1, i2);
         return true;
     }
 
+    // same color mutations as a polygon, the alpha stays though (opaque stays opaque, transparent stays transparent)
+    pub fn mutate_background(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
+        if self.background.a == 0 {
+            return false;
+        }
+        let before = self.background;
+        self.background.mutate(settings, rng);
+        self.background.a = before.a;
+        self.background != before
+    }
+
+    // render targets get cleared to transparent, the background is 2 triangles underneath the shapes
     pub fn to_vertices(&self) -> Vec<Vertex> {
-        // FIXME: hacky workaround --> 2 white triangles as background seems to fix blending issues
-        let mut background = vec![
-            Vertex {
-                position: [
-                    translate_coord(0.0f32),
-                    translate_coord(0.0f32),
-                    0.0f32,
-                    1.0f32,
-                ],
-                color: [1.0f32, 1.0f32, 1.0f32, 1.0f32],
-            },
-            Vertex {
-                position: [
-                    translate_coord(1.0f32),
-                    translate_coord(0.0f32),
-                    0.0f32,
-                    1.0f32,
-                ],
-                color: [1.0f32, 1.0f32, 1.0f32, 1.0f32],
-            },
-            Vertex {
-                position: [
-                    translate_coord(1.0f32),
-                    translate_coord(1.0f32),
-                    0.0f32,
-                    1.0f32,
-                ],
-                color: [1.0f32, 1.0f32, 1.0f32, 1.0f32],
-            },
-            Vertex {
-                position: [
-                    translate_coord(0.0f32),
-                    translate_coord(0.0f32),
-                    0.0f32,
-                    1.0f32,
-                ],
-                color: [1.0f32, 1.0f32, 1.0f32, 1.0f32],
-            },
-            Vertex {
-                position: [
-                    translate_coord(0.0f32),
-                    translate_coord(1.0f32),
-                    0.0f32,
-                    1.0f32,
-                ],
-                color: [1.0f32, 1.0f32, 1.0f32, 1.0f32],
-            },
-            Vertex {
-                position: [
-                    translate_coord(1.0f32),
-                    translate_coord(1.0f32),
-                    0.0f32,
-                    1.0f32,
-                ],
-                color: [1.0f32, 1.0f32, 1.0f32, 1.0f32],
-            },
-        ];
-
-        let vert: Vec<Vertex> = self
-            .clone()
-            .polygons
-            .into_iter()
-            .map(|pp| {
-                let arr: Vec<Vertex> = pp
-                    .points
-                    .into_iter()
-                    .map(|p| Vertex {
-                        position: [
-                            translate_coord(p.x),
-                            translate_coord(1.0 - p.y),
-                            0.0f32,
-                            1.0f32,
-                        ],
-                        color: [
-                            translate_color(pp.color.r),
-                            translate_color(pp.color.g),
-                            translate_color(pp.color.b),
-                            translate_color(pp.color.a),
-                        ],
-                    })
-                    .collect();
-                arr
-            })
-            .flatten()
-            .collect();
-
-        background.extend(vert);
-        background
+        let mut vertices = vec![];
+        self.write_vertices(&mut vertices, &mut Triangulation::default());
+        vertices
+    }
+
+    // most vertices write_vertices produces for a drawing of polygons within the settings' limits,
+    // the GPU backend starts out with that much room per drawing, strokes can take more
+    pub fn max_vertices(settings: &Settings) -> usize {
+        6 + settings.max_polygons_per_image * settings.max_points_per_polygon.saturating_sub(2) * 3
+    }
+
+    // appends what to_vertices returns, so the caller can reuse the same Vecs for every drawing
+    pub fn write_vertices(&self, vertices: &mut Vec<Vertex>, triangulation: &mut Triangulation) {
+        if self.background.a != 0 {
+            let color = vertex_color(&self.background);
+            vertices.extend(
+                [
+                    (0.0, 0.0),
+                    (1.0, 0.0),
+                    (1.0, 1.0),
+                    (0.0, 0.0),
+                    (0.0, 1.0),
+                    (1.0, 1.0),
+                ]
+                .map(|(x, y): (f32, f32)| Vertex {
+                    position: [translate_coord(x), translate_coord(y), 0.0f32, 1.0f32],
+                    color,
+                    ..Zeroable::zeroed()
+                }),
+            );
+        }
+        for shape in &self.shapes {
+            match shape {
+                Shape::Polygon(polygon) => polygon_vertices(polygon, triangulation, vertices),
+                Shape::Ellipse(ellipse) => ellipse_vertices(ellipse, vertices),
+                Shape::Stroke(stroke) => stroke_vertices(stroke, vertices),
+            }
+        }
     }
 }
 
-impl From<JsValue> for Drawing {
-    fn from(value: JsValue) -> Self {
-        let stringified = JsValue::as_string(&value).expect("Expected stringified Drawing.");
-        Drawing::from(stringified)
+// json of a drawing, also what JS passes around
+impl TryFrom<JsValue> for Drawing {
+    type Error = Error;
+
+    fn try_from(value: JsValue) -> Result<Drawing> {
+        value
+            .as_string()
+            .context("expected a drawing as a json string")?
+            .parse()
     }
 }
 
-impl From<String> for Drawing {
-    fn from(json: String) -> Self {
-        serde_json::from_str(&json).expect(&format!("Expected deserializable Drawing.\n{}", json))
+impl FromStr for Drawing {
+    type Err = Error;
+
+    fn from_str(json: &str) -> Result<Drawing> {
+        let drawing: Drawing = serde_json::from_str(json).context("invalid drawing json")?;
+        drawing.validate()?;
+        Ok(drawing)
     }
 }
 
+// <linearGradient> / <radialGradient> with id g{id}, None for solid polygons
+// in 0..1 and stretched by the transform, like the rest of the shapes
+fn svg_gradient(polygon: &Polygon, id: usize, width: usize, height: usize) -> Option<String> {
+    let stop = |offset: f32, c: &Color| {
+        format!(
+            "<stop offset=\"{}\" stop-color=\"rgb({},{},{})\" stop-opacity=\"{}\"/>",
+            offset,
+            c.r,
+            c.g,
+            c.b,
+            c.a as f32 / 255.0
+        )
+    };
+    let units = format!(
+        "gradientUnits=\"userSpaceOnUse\" gradientTransform=\"scale({} {})\"",
+        width, height
+    );
+    match &polygon.fill {
+        Fill::Solid => None,
+        Fill::Linear { start, end, color } => Some(format!(
+            "<linearGradient id=\"g{}\" {} x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\">{}{}</linearGradient>",
+            id,
+            units,
+            start.x,
+            start.y,
+            end.x,
+            end.y,
+            stop(0.0, &polygon.color),
+            stop(1.0, color)
+        )),
+        Fill::Radial {
+            center,
+            radius,
+            color,
+        } => Some(format!(
+            "<radialGradient id=\"g{}\" {} cx=\"{}\" cy=\"{}\" r=\"{}\">{}{}</radialGradient>",
+            id,
+            units,
+            center.x,
+            center.y,
+            radius,
+            stop(0.0, &polygon.color),
+            stop(1.0, color)
+        )),
+    }
+}
+
+fn css_color(c: &Color) -> String {
+    format!("rgba({},{},{},{})", c.r, c.g, c.b, c.a as f32 / 255.0)
+}
+
+// fill or stroke attributes for a color
+fn svg_paint(attribute: &str, c: &Color) -> String {
+    format!(
+        "{a}=\"rgb({},{},{})\" {a}-opacity=\"{}\"",
+        c.r,
+        c.g,
+        c.b,
+        c.a as f32 / 255.0,
+        a = attribute
+    )
+}
+
+// gradients get evaluated per fragment, shape.xy is the point in 0..1 coordinates and shape.w the kind of fill
+fn polygon_vertices(
+    polygon: &Polygon,
+    triangulation: &mut Triangulation,
+    vertices: &mut Vec<Vertex>,
+) {
+    let color = vertex_color(&polygon.color);
+    let (kind, gradient, gradient_color) = match &polygon.fill {
+        Fill::Solid => (0.0, [0.0; 4], [0.0; 4]),
+        Fill::Linear { start, end, color } => {
+            (1.0, [start.x, start.y, end.x, end.y], vertex_color(color))
+        }
+        Fill::Radial {
+            center,
+            radius,
+            color,
+        } => (2.0, [center.x, center.y, *radius, 0.0], vertex_color(color)),
+    };
+    polygon.triangulate(triangulation);
+    vertices.extend(triangulation.triangles.iter().flatten().map(|&i| {
+        let p = polygon.points[i];
+        Vertex {
+            position: [
+                translate_coord(p.x),
+                translate_coord(1.0 - p.y),
+                0.0f32,
+                1.0f32,
+            ],
+            color,
+            shape: [p.x, p.y, 0.0, kind],
+            gradient,
+            gradient_color,
+        }
+    }));
+}
+
+// a quad around the ellipse, shape.xy is the position relative to the radii (the edge is the unit circle)
+// and shape.z = 1 tells fs_main to evaluate the SDF
+fn ellipse_vertices(ellipse: &Ellipse, vertices: &mut Vec<Vertex>) {
+    let color = vertex_color(&ellipse.color);
+    let corners = ellipse.corners();
+    let local = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+    vertices.extend([0, 1, 2, 0, 2, 3].map(|i| Vertex {
+        position: [
+            translate_coord(corners[i].x),
+            translate_coord(1.0 - corners[i].y),
+            0.0f32,
+            1.0f32,
+        ],
+        color,
+        shape: [local[i].0, local[i].1, 1.0, 0.0],
+        ..Zeroable::zeroed()
+    }));
+}
+
+fn stroke_vertices(stroke: &Stroke, vertices: &mut Vec<Vertex>) {
+    let color = vertex_color(&stroke.color);
+    vertices.extend(stroke.triangles().into_iter().map(|p| Vertex {
+        position: [
+            translate_coord(p.x),
+            translate_coord(1.0 - p.y),
+            0.0f32,
+            1.0f32,
+        ],
+        color,
+        ..Zeroable::zeroed()
+    }));
+}
+
+fn vertex_color(color: &Color) -> [f32; 4] {
+    [
+        translate_color(color.r),
+        translate_color(color.g),
+        translate_color(color.b),
+        translate_color(color.a),
+    ]
+}
+
 fn translate_coord(number: f32) -> f32 {
     return number * 2.0 - 1.0;
 }
@@ -264,3 +638,134 @@ fn translate_coord(number: f32) -> f32 {
 fn translate_color(color: u8) -> f32 {
     color as f32 / 255.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::seeded_rng;
+
+    fn drawing(shapes: usize, settings: &Settings, rng: &mut SeededRng) -> Drawing {
+        let settings = Settings {
+            start_with_polygons_per_image: shapes,
+            ..*settings
+        };
+        Drawing::new_random(Color::TRANSPARENT, &settings, rng)
+    }
+
+    fn triangle(x: f32, y: f32) -> Shape {
+        Shape::Polygon(Polygon {
+            points: vec![
+                Point { x, y },
+                Point { x: x + 0.1, y },
+                Point { x, y: y + 0.1 },
+            ],
+            color: Color::WHITE,
+            fill: Fill::Solid,
+        })
+    }
+
+    fn shapes(shapes: Vec<Shape>) -> Drawing {
+        Drawing {
+            shapes,
+            is_dirty: false,
+            fitness: 0.0,
+            background: Color::WHITE,
+        }
+    }
+
+    fn assert_bounds(a: &Drawing, b: &Drawing, min: (f32, f32), max: (f32, f32)) {
+        let (actual_min, actual_max) = a.changed_bounds(b).expect("Expected a change.");
+        for (actual, expected) in [(actual_min, min), (actual_max, max)] {
+            assert!((actual.x - expected.0).abs() < 1e-6, "{:?}", actual);
+            assert!((actual.y - expected.1).abs() < 1e-6, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn changed_bounds() {
+        let base = shapes(vec![
+            triangle(0.1, 0.1),
+            triangle(0.5, 0.5),
+            triangle(0.8, 0.2),
+        ]);
+        assert_eq!(base.changed_bounds(&base.clone()), None);
+
+        // moved: where it was and where it is now
+        let mut moved = base.clone();
+        moved.shapes[1] = triangle(0.6, 0.3);
+        assert_bounds(&base, &moved, (0.5, 0.3), (0.7, 0.6));
+
+        // swapped: both shapes, the one in between isn't affected
+        let mut swapped = base.clone();
+        swapped.shapes.swap(0, 2);
+        assert_bounds(&base, &swapped, (0.1, 0.1), (0.9, 0.3));
+
+        // added or removed in the middle: just that shape
+        let mut added = base.clone();
+        added.shapes.insert(1, triangle(0.3, 0.7));
+        assert_bounds(&base, &added, (0.3, 0.7), (0.4, 0.8));
+        assert_bounds(&added, &base, (0.3, 0.7), (0.4, 0.8));
+
+        // at either end
+        let mut appended = base.clone();
+        appended.shapes.push(triangle(0.0, 0.0));
+        assert_bounds(&base, &appended, (0.0, 0.0), (0.1, 0.1));
+        let mut removed = base.clone();
+        removed.shapes.remove(0);
+        assert_bounds(&base, &removed, (0.1, 0.1), (0.2, 0.2));
+
+        // a copy of a neighbour added next to it is still only one shape
+        let mut duplicated = base.clone();
+        duplicated.shapes.insert(1, triangle(0.5, 0.5));
+        assert_bounds(&base, &duplicated, (0.5, 0.5), (0.6, 0.6));
+
+        let mut background = base.clone();
+        background.background = Color::TRANSPARENT;
+        assert_bounds(&base, &background, (0.0, 0.0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn shape_mutations_work_with_few_shapes() {
+        let settings = Settings {
+            min_polygons_per_image: 0,
+            ..Settings::default()
+        };
+        let mut rng = seeded_rng(1);
+        for shapes in 0..3 {
+            for _ in 0..50 {
+                let mut d = drawing(shapes, &settings, &mut rng);
+                assert!(d.add_shape(&settings, None, &mut rng));
+                assert_eq!(d.shapes.len(), shapes + 1);
+
+                let mut d = drawing(shapes, &settings, &mut rng);
+                assert_eq!(d.remove_shape(&settings, &mut rng), shapes > 0);
+
+                let mut d = drawing(shapes, &settings, &mut rng);
+                let before = d.shapes.clone();
+                assert_eq!(d.reorder_shapes(&mut rng), shapes > 1);
+                if shapes == 2 {
+                    assert_eq!(d.shapes, [before[1].clone(), before[0].clone()]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn strokes_need_3_or_4_points() {
+        for points in 0..7 {
+            let stroke = Shape::Stroke(Stroke {
+                points: vec![Point { x: 0.5, y: 0.5 }; points],
+                width: 0.01,
+                color: Color::WHITE,
+            });
+            let json = serde_json::to_string(&shapes(vec![triangle(0.1, 0.1), stroke])).unwrap();
+            assert_eq!(
+                json.parse::<Drawing>().is_ok(),
+                matches!(points, 3 | 4),
+                "{}",
+                points
+            );
+        }
+        assert!("{\"shapes\":".parse::<Drawing>().is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::f32::consts::PI;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{randomf32_clamped, SeededRng};
+
+use super::{
+    color::Color,
+    error_map::{new_color, new_origin, ErrorMap},
+    point::Point,
+    settings::Settings,
+};
+
+// smaller ellipses don't cover a pixel anymore, also keeps the SDF away from dividing by 0
+pub const MIN_RADIUS: f32 = 0.001;
+
+// biggest turn of a single rotate mutation, an ellipse looks the same after half a turn
+const MAX_ROTATION_DELTA: f32 = PI / 4.0;
+
+// a soft edged round shape, rendered as a quad whose fragment shader evaluates the ellipse's SDF
+// all in the same 0..1 coordinates as polygon points, so on a non-square image a circle gets stretched like a polygon would
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Ellipse {
+    pub center: Point,
+    pub rx: f32,
+    pub ry: f32,
+    pub rotation: f32, // radians, clockwise (y points down) like Canvas2D's ellipse() and svg's rotate()
+    pub color: Color,
+}
+
+impl Ellipse {
+    pub fn new_random(
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> Ellipse {
+        let center = new_origin(error_map, rng);
+        let d = settings.new_point_max_distance;
+        Ellipse {
+            center,
+            rx: randomf32_clamped(MIN_RADIUS, d.max(MIN_RADIUS * 2.0), rng),
+            ry: randomf32_clamped(MIN_RADIUS, d.max(MIN_RADIUS * 2.0), rng),
+            rotation: randomf32_clamped(0.0, PI, rng),
+            color: new_color(center, error_map, settings, rng),
+        }
+    }
+
+    // the 4 corners of the rotated bounding box, clockwise from (-rx, -ry)
+    pub fn corners(&self) -> [Point; 4] {
+        let (sin, cos) = self.rotation.sin_cos();
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].map(|(u, v)| {
+            let (x, y) = (u * self.rx, v * self.ry);
+            Point {
+                x: self.center.x + cos * x - sin * y,
+                y: self.center.y + sin * x + cos * y,
+            }
+        })
+    }
+
+    pub fn mutate(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> bool {
+        let mut mutated = false;
+        if rng.gen::<f32>() < settings.resize_ellipse_probability {
+            let d = settings.move_point_max_delta;
+            self.rx = randomf32_clamped(self.rx - d, self.rx + d, rng).clamp(MIN_RADIUS, 1.0);
+            self.ry = randomf32_clamped(self.ry - d, self.ry + d, rng).clamp(MIN_RADIUS, 1.0);
+            mutated = true;
+        }
+        if rng.gen::<f32>() < settings.rotate_ellipse_probability {
+            let delta = randomf32_clamped(-MAX_ROTATION_DELTA, MAX_ROTATION_DELTA, rng);
+            self.rotation = (self.rotation + delta).rem_euclid(PI);
+            mutated = true;
+        }
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            let d = settings.micro_adjustment_delta;
+            self.rx = randomf32_clamped(self.rx - d, self.rx + d, rng).clamp(MIN_RADIUS, 1.0);
+            self.ry = randomf32_clamped(self.ry - d, self.ry + d, rng).clamp(MIN_RADIUS, 1.0);
+            mutated = true;
+        }
+
+        if self.color.mutate(settings, rng) {
+            mutated = true;
+        }
+        if self.center.mutate(settings, error_map, rng) {
+            mutated = true;
+        }
+
+        mutated
+    }
+}
@@ -0,0 +1,112 @@
+use rand::Rng;
+
+use crate::util::{randomf32_clamped, SeededRng};
+
+use super::{color::Color, point::Point, settings::Settings};
+
+// candidates a guided point move looks at before settling for the last one
+const MAX_TRIES: usize = 8;
+
+// per pixel error of the best drawing, new shapes and moved points go where it's high
+// so mutations don't keep trying areas that are already right, see Settings::error_guided_placement
+pub struct ErrorMap {
+    width: usize,
+    height: usize,
+    errors: Vec<f32>,
+    cumulative: Vec<f64>, // running total of errors, binary searched to pick pixels in proportion to their error
+    max: f32,
+    source_bytes: Vec<u8>, // straight alpha RGBA8 target at the same size, new shapes take their color from it
+}
+
+impl ErrorMap {
+    pub fn new(errors: Vec<f32>, source_bytes: Vec<u8>, width: usize, height: usize) -> ErrorMap {
+        assert_eq!(errors.len(), width * height);
+        let mut total = 0.0;
+        let cumulative = errors
+            .iter()
+            .map(|&e| {
+                total += e.max(0.0) as f64;
+                total
+            })
+            .collect();
+        let max = errors.iter().fold(0.0f32, |max, &e| max.max(e));
+        ErrorMap {
+            width,
+            height,
+            errors,
+            cumulative,
+            max,
+            source_bytes,
+        }
+    }
+
+    // a pixel picked in proportion to its error, anywhere inside it
+    pub fn sample(&self, rng: &mut SeededRng) -> Point {
+        let total = *self.cumulative.last().unwrap_or(&0.0);
+        if total <= 0.0 {
+            return Point::new_random(rng); // nothing left to fix
+        }
+        let target = rng.gen::<f64>() * total;
+        let index = self
+            .cumulative
+            .partition_point(|&c| c <= target)
+            .min(self.errors.len() - 1);
+        Point {
+            x: ((index % self.width) as f32 + rng.gen::<f32>()) / self.width as f32,
+            y: ((index / self.width) as f32 + rng.gen::<f32>()) / self.height as f32,
+        }
+    }
+
+    // somewhere within d of point, rejection sampled so higher errors are more likely
+    pub fn sample_near(&self, point: Point, d: f32, rng: &mut SeededRng) -> Point {
+        let mut candidate = point;
+        for _ in 0..MAX_TRIES {
+            candidate = Point {
+                x: randomf32_clamped(point.x - d, point.x + d, rng).clamp(0.0, 1.0),
+                y: randomf32_clamped(point.y - d, point.y + d, rng).clamp(0.0, 1.0),
+            };
+            if self.max <= 0.0 || rng.gen::<f32>() * self.max < self.errors[self.index(candidate)] {
+                break;
+            }
+        }
+        candidate
+    }
+
+    // the target's color at point with a random alpha like Color::new_random
+    pub fn color_at(&self, point: Point, settings: &Settings, rng: &mut SeededRng) -> Color {
+        let i = self.index(point) * 4;
+        Color {
+            r: self.source_bytes[i],
+            g: self.source_bytes[i + 1],
+            b: self.source_bytes[i + 2],
+            a: Color::random_alpha(settings, rng),
+        }
+    }
+
+    fn index(&self, point: Point) -> usize {
+        let x = ((point.x * self.width as f32) as usize).min(self.width - 1);
+        let y = ((point.y * self.height as f32) as usize).min(self.height - 1);
+        y * self.width + x
+    }
+}
+
+// where a new shape goes, uniformly random without an error map
+pub fn new_origin(error_map: Option<&ErrorMap>, rng: &mut SeededRng) -> Point {
+    match error_map {
+        Some(error_map) => error_map.sample(rng),
+        None => Point::new_random(rng),
+    }
+}
+
+// color of a new shape at origin, entirely random without an error map
+pub fn new_color(
+    origin: Point,
+    error_map: Option<&ErrorMap>,
+    settings: &Settings,
+    rng: &mut SeededRng,
+) -> Color {
+    match error_map {
+        Some(error_map) => error_map.color_at(origin, settings, rng),
+        None => Color::new_random(settings, rng),
+    }
+}
@@ -0,0 +1,100 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{randomf32_clamped, SeededRng};
+
+use super::{
+    color::Color,
+    point::{bounds, Point},
+    settings::Settings,
+};
+
+// smallest radial gradient, also keeps the shaders from dividing by 0
+pub const MIN_RADIUS: f32 = 0.001;
+
+// how a polygon is painted, gradients go from the polygon's color to the color in here
+// geometry is in the same 0..1 coordinates as the points, colors are interpolated premultiplied like Canvas2D does
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Fill {
+    #[default]
+    Solid,
+    Linear {
+        start: Point, // the polygon's color at and before start
+        end: Point,   // color at and after end, constant along lines perpendicular to start -> end
+        color: Color,
+    },
+    Radial {
+        center: Point, // the polygon's color in the center
+        radius: f32,   // color from the radius on
+        color: Color,
+    },
+}
+
+impl Fill {
+    pub fn is_solid(&self) -> bool {
+        *self == Fill::Solid
+    }
+
+    // a random kind with geometry somewhere around the points
+    pub fn new_random(points: &[Point], settings: &Settings, rng: &mut SeededRng) -> Fill {
+        let (min, max) = bounds(points);
+        let mut inside = || Point {
+            x: if min.x < max.x {
+                randomf32_clamped(min.x, max.x, rng)
+            } else {
+                min.x
+            },
+            y: if min.y < max.y {
+                randomf32_clamped(min.y, max.y, rng)
+            } else {
+                min.y
+            },
+        };
+        let (start, end) = (inside(), inside());
+        let color = Color::new_random(settings, rng);
+        match rng.gen_range(0..3) {
+            0 => Fill::Solid,
+            1 => Fill::Linear { start, end, color },
+            _ => Fill::Radial {
+                center: start,
+                radius: f32::hypot(max.x - min.x, max.y - min.y).max(MIN_RADIUS * 2.0) * 0.5,
+                color,
+            },
+        }
+    }
+
+    // the second stop's color and the geometry, the first stop is the polygon's color and gets mutated with it
+    pub fn mutate(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
+        match self {
+            Fill::Solid => false,
+            Fill::Linear { start, end, color } => {
+                let mut mutated = color.mutate(settings, rng);
+                mutated |= start.mutate(settings, None, rng);
+                mutated |= end.mutate(settings, None, rng);
+                mutated
+            }
+            Fill::Radial {
+                center,
+                radius,
+                color,
+            } => {
+                let mut mutated = color.mutate(settings, rng);
+                mutated |= center.mutate(settings, None, rng);
+                if rng.gen::<f32>() < settings.move_point_probability {
+                    let d = settings.move_point_max_delta;
+                    *radius =
+                        randomf32_clamped(*radius - d, *radius + d, rng).clamp(MIN_RADIUS, 1.0);
+                    mutated = true;
+                }
+                if rng.gen::<f32>() < settings.micro_adjustment_probability {
+                    let d = settings.micro_adjustment_delta;
+                    *radius =
+                        randomf32_clamped(*radius - d, *radius + d, rng).clamp(MIN_RADIUS, 1.0);
+                    mutated = true;
+                }
+                mutated
+            }
+        }
+    }
+}
@@ -1,12 +1,9 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::util::randomf32_clamped;
+use crate::util::{randomf32_clamped, SeededRng};
 
-use super::settings::{
-    MICRO_ADJUSTMENT_DELTA, MICRO_ADJUSTMENT_PROBABILITY, MOVE_POINT_MAX_DELTA,
-    MOVE_POINT_PROBABILITY,
-};
+use super::{error_map::ErrorMap, settings::Settings};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct Point {
@@ -15,10 +12,10 @@ pub struct Point {
 }
 
 impl Point {
-    pub fn new_random() -> Point {
+    pub fn new_random(rng: &mut SeededRng) -> Point {
         Point {
-            x: rand::thread_rng().gen::<f32>(),
-            y: rand::thread_rng().gen::<f32>(),
+            x: rng.gen::<f32>(),
+            y: rng.gen::<f32>(),
         }
     }
 
@@ -27,25 +24,55 @@ impl Point {
         self.y = (self.y + y_offset).clamp(0.0, 1.0);
     }
 
-    pub fn mutate(&mut self) -> bool {
+    // with an error map big moves favour the parts of their range that are furthest off
+    pub fn mutate(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> bool {
         let mut mutated = false;
-        if rand::thread_rng().gen::<f32>() < MOVE_POINT_PROBABILITY {
-            let d = MOVE_POINT_MAX_DELTA;
-            self.x = randomf32_clamped(self.x - d, self.x + d).clamp(0.0, 1.0);
-            self.y = randomf32_clamped(self.y - d, self.y + d).clamp(0.0, 1.0);
+        if rng.gen::<f32>() < settings.move_point_probability {
+            let d = settings.move_point_max_delta;
+            match error_map {
+                Some(error_map) => *self = error_map.sample_near(*self, d, rng),
+                None => {
+                    self.x = randomf32_clamped(self.x - d, self.x + d, rng).clamp(0.0, 1.0);
+                    self.y = randomf32_clamped(self.y - d, self.y + d, rng).clamp(0.0, 1.0);
+                }
+            }
             mutated = true;
         }
 
-        if rand::thread_rng().gen::<f32>() < MICRO_ADJUSTMENT_PROBABILITY {
-            let d = MICRO_ADJUSTMENT_DELTA;
-            self.x = randomf32_clamped(self.x - d, self.x + d).clamp(0.0, 1.0);
-            self.y = randomf32_clamped(self.y - d, self.y + d).clamp(0.0, 1.0);
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            let d = settings.micro_adjustment_delta;
+            self.x = randomf32_clamped(self.x - d, self.x + d, rng).clamp(0.0, 1.0);
+            self.y = randomf32_clamped(self.y - d, self.y + d, rng).clamp(0.0, 1.0);
             mutated = true;
         }
         mutated
     }
 }
 
+// smallest box around the points as (min, max)
+pub fn bounds(points: &[Point]) -> (Point, Point) {
+    points.iter().fold(
+        (Point { x: 1.0, y: 1.0 }, Point { x: 0.0, y: 0.0 }),
+        |(min, max), p| {
+            (
+                Point {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Point {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        },
+    )
+}
+
 impl Eq for Point {}
 
 impl Ord for Point {
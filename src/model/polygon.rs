@@ -1,21 +1,31 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::util::randomf32_clamped;
+use crate::util::{randomf32_clamped, SeededRng};
 
 use super::{
     color::Color,
+    error_map::{new_color, new_origin, ErrorMap},
+    fill::Fill,
     point::Point,
-    settings::{
-        MIN_POINTS_PER_POLYGON, NEW_POINT_MAX_DISTANCE, OFFSET_POLYGON_MAGNITUDE,
-        OFFSET_POLYGON_PROBABILITY, REMOVE_POINT_PROBABILITY,
-    },
+    settings::Settings,
 };
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+// what Polygon::triangulate works in, kept by the caller so triangulating every polygon of every drawing
+// doesn't allocate once it has grown to the most points per polygon
+#[derive(Default)]
+pub struct Triangulation {
+    pub triangles: Vec<[usize; 3]>,
+    remaining: Vec<usize>, // corners not clipped yet
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Polygon {
     pub points: Vec<Point>,
     pub color: Color,
+    #[serde(default, skip_serializing_if = "Fill::is_solid")]
+    // solid polygons look like they did before gradients
+    pub fill: Fill,
 }
 
 impl Polygon {
@@ -23,69 +33,329 @@ impl Polygon {
         self.points.len()
     }
 
-    pub fn new_random() -> Polygon {
-        let origin: Point = Point::new_random();
-        let d = NEW_POINT_MAX_DISTANCE;
+    pub fn new_random(
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> Polygon {
+        let origin: Point = new_origin(error_map, rng);
+        let d = settings.new_point_max_distance;
         let points = (0..3)
             .map(|_| {
-                let x = randomf32_clamped(origin.x - d, origin.x + d).clamp(0.0, 1.0);
-                let y = randomf32_clamped(origin.y - d, origin.y + d).clamp(0.0, 1.0);
+                let x = randomf32_clamped(origin.x - d, origin.x + d, rng).clamp(0.0, 1.0);
+                let y = randomf32_clamped(origin.y - d, origin.y + d, rng).clamp(0.0, 1.0);
                 return Point { x, y };
             })
             .collect();
         Polygon {
             points,
-            color: Color::new_random(),
+            color: new_color(origin, error_map, settings, rng),
+            fill: Fill::Solid,
         }
     }
 
-    pub fn offset_polygon(&mut self) -> bool {
+    pub fn offset_polygon(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
         if self.points.len() < 3 {
             return false;
         }
 
-        let x_offset = randomf32_clamped(-OFFSET_POLYGON_MAGNITUDE, OFFSET_POLYGON_MAGNITUDE);
-        let y_offset = randomf32_clamped(-OFFSET_POLYGON_MAGNITUDE, OFFSET_POLYGON_MAGNITUDE);
+        let magnitude = settings.offset_polygon_magnitude;
+        let x_offset = randomf32_clamped(-magnitude, magnitude, rng);
+        let y_offset = randomf32_clamped(-magnitude, magnitude, rng);
         self.points
             .iter_mut()
             .for_each(|point| point.offset(x_offset, y_offset));
+        match &mut self.fill {
+            Fill::Solid => {}
+            Fill::Linear { start, end, .. } => {
+                start.offset(x_offset, y_offset);
+                end.offset(x_offset, y_offset);
+            }
+            Fill::Radial { center, .. } => center.offset(x_offset, y_offset),
+        }
 
         true
     }
 
-    pub fn remove_point(&mut self) -> bool {
+    // splits a random edge, the new point lands near its middle so the shape only changes a little
+    pub fn add_point(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
+        let n = self.points.len();
+        if n >= settings.max_points_per_polygon {
+            return false;
+        }
+        let i = rng.gen_range(0..n);
+        let (a, b) = (self.points[i], self.points[(i + 1) % n]);
+        let d = settings.new_point_max_distance;
+        let x = randomf32_clamped((a.x + b.x) * 0.5 - d, (a.x + b.x) * 0.5 + d, rng);
+        let y = randomf32_clamped((a.y + b.y) * 0.5 - d, (a.y + b.y) * 0.5 + d, rng);
+        self.points.insert(
+            i + 1,
+            Point {
+                x: x.clamp(0.0, 1.0),
+                y: y.clamp(0.0, 1.0),
+            },
+        );
+        true
+    }
+
+    pub fn remove_point(&mut self, settings: &Settings, rng: &mut SeededRng) -> bool {
         let n = self.points.len();
-        if n <= MIN_POINTS_PER_POLYGON {
+        if n <= settings.min_points_per_polygon {
             return false;
         }
-        let i = rand::thread_rng().gen_range(0..(n - 1));
+        let i = rng.gen_range(0..n);
         self.points.remove(i);
         true
     }
 
-    pub fn mutate(&mut self) -> bool {
+    pub fn mutate(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> bool {
         let mut mutated = false;
-        if rand::thread_rng().gen::<f32>() < OFFSET_POLYGON_PROBABILITY {
-            if self.offset_polygon() {
+        if rng.gen::<f32>() < settings.offset_polygon_probability {
+            if self.offset_polygon(settings, rng) {
+                mutated = true;
+            }
+        }
+        if rng.gen::<f32>() < settings.add_point_probability && self.add_point(settings, rng) {
+            mutated = true;
+        }
+        if rng.gen::<f32>() < settings.remove_point_probability {
+            if self.remove_point(settings, rng) {
                 mutated = true;
             }
         }
-        if rand::thread_rng().gen::<f32>() < REMOVE_POINT_PROBABILITY {
-            if self.remove_point() {
+
+        if rng.gen::<f32>() < settings.change_fill_probability {
+            let fill = Fill::new_random(&self.points, settings, rng);
+            if fill != self.fill {
+                self.fill = fill;
                 mutated = true;
             }
         }
 
-        if self.color.mutate() {
+        if self.color.mutate(settings, rng) {
             mutated = true
         }
+        if self.fill.mutate(settings, rng) {
+            mutated = true;
+        }
 
         self.points.iter_mut().for_each(|p| {
-            if p.mutate() {
+            if p.mutate(settings, error_map, rng) {
                 mutated = true;
             }
         });
 
         mutated
     }
+
+    // indices into points, 3 per triangle, covering the same area as Canvas2D's fill()
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut triangulation = Triangulation::default();
+        self.triangulate(&mut triangulation);
+        triangulation.triangles
+    }
+
+    // replaces triangulation.triangles with what triangles returns
+    // convex polygons become a fan, anything else gets ear clipped
+    pub fn triangulate(&self, triangulation: &mut Triangulation) {
+        let Triangulation {
+            triangles,
+            remaining,
+        } = triangulation;
+        triangles.clear();
+        let n = self.points.len();
+        if n < 3 {
+            return;
+        }
+        let orientation = signed_area(&self.points).signum();
+        let turns = (0..n).map(|i| {
+            orientation
+                * cross(
+                    self.points[(i + n - 1) % n],
+                    self.points[i],
+                    self.points[(i + 1) % n],
+                )
+        });
+        if turns.into_iter().all(|turn| turn >= 0.0) {
+            triangles.extend((1..n - 1).map(|i| [0, i, i + 1]));
+            return;
+        }
+
+        remaining.clear();
+        remaining.extend(0..n);
+        let (mut i, mut misses) = (0, 0);
+        while remaining.len() > 3 {
+            let m = remaining.len();
+            i %= m;
+            let corner = [
+                remaining[(i + m - 1) % m],
+                remaining[i],
+                remaining[(i + 1) % m],
+            ];
+            // self-intersecting polygons can run out of ears, clip anyway so they still render something
+            if misses >= m || self.is_ear(corner, remaining, orientation) {
+                triangles.push(corner);
+                remaining.remove(i);
+                misses = 0;
+            } else {
+                i += 1;
+                misses += 1;
+            }
+        }
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+
+    // convex corner (or a degenerate one, clipping that adds an empty triangle) with no other point inside
+    fn is_ear(&self, [a, b, c]: [usize; 3], remaining: &[usize], orientation: f32) -> bool {
+        let (pa, pb, pc) = (self.points[a], self.points[b], self.points[c]);
+        if orientation * cross(pa, pb, pc) < 0.0 {
+            return false;
+        }
+        !remaining.iter().any(|&j| {
+            let p = self.points[j];
+            j != a
+                && j != b
+                && j != c
+                && p != pa
+                && p != pb
+                && p != pc
+                && orientation * cross(pa, pb, p) >= 0.0
+                && orientation * cross(pb, pc, p) >= 0.0
+                && orientation * cross(pc, pa, p) >= 0.0
+        })
+    }
+}
+
+// > 0 when a -> b -> c turns the same way as a polygon with a positive signed_area
+fn cross(a: Point, b: Point, c: Point) -> f32 {
+    (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x)
+}
+
+// shoelace formula, the sign tells the winding
+fn signed_area(points: &[Point]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f32>()
+        * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f32, f32)]) -> Polygon {
+        Polygon {
+            points: points.iter().map(|&(x, y)| Point { x, y }).collect(),
+            color: Color::TRANSPARENT,
+            fill: Fill::Solid,
+        }
+    }
+
+    // n - 2 triangles of valid indices, wound like the polygon and adding up to its area
+    fn assert_covers(polygon: &Polygon) {
+        let triangles = polygon.triangles();
+        let n = polygon.points.len();
+        assert_eq!(triangles.len(), n - 2);
+        let area = signed_area(&polygon.points);
+        let mut total = 0.0;
+        for triangle in &triangles {
+            assert!(triangle.iter().all(|&i| i < n));
+            let corners = triangle.map(|i| polygon.points[i]);
+            let triangle_area = signed_area(&corners);
+            assert!(triangle_area * area.signum() >= 0.0, "{:?}", triangle);
+            total += triangle_area.abs();
+        }
+        assert!((total - area.abs()).abs() < 1e-5, "{} vs {}", total, area);
+    }
+
+    fn reversed(points: &[(f32, f32)]) -> Vec<(f32, f32)> {
+        points.iter().rev().copied().collect()
+    }
+
+    const SQUARE: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+    const ARROW: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.5), (0.0, 1.0), (0.3, 0.5)];
+    const L: [(f32, f32); 6] = [
+        (0.0, 0.0),
+        (1.0, 0.0),
+        (1.0, 0.4),
+        (0.4, 0.4),
+        (0.4, 1.0),
+        (0.0, 1.0),
+    ];
+
+    #[test]
+    fn convex_is_a_fan() {
+        for points in [SQUARE.to_vec(), reversed(&SQUARE)] {
+            let polygon = polygon(&points);
+            assert_eq!(polygon.triangles(), [[0, 1, 2], [0, 2, 3]]);
+            assert_covers(&polygon);
+        }
+    }
+
+    #[test]
+    fn concave_both_windings() {
+        for points in [ARROW.to_vec(), L.to_vec()] {
+            assert_covers(&polygon(&points));
+            assert_covers(&polygon(&reversed(&points)));
+        }
+    }
+
+    #[test]
+    fn collinear_points() {
+        let square = [(0.0, 0.0), (0.5, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        assert_covers(&polygon(&square));
+        assert_covers(&polygon(&reversed(&square)));
+        let l = [
+            (0.0, 0.0),
+            (0.5, 0.0),
+            (1.0, 0.0),
+            (1.0, 0.4),
+            (0.4, 0.4),
+            (0.4, 0.7),
+            (0.4, 1.0),
+            (0.0, 1.0),
+        ];
+        assert_covers(&polygon(&l));
+        assert_covers(&polygon(&reversed(&l)));
+    }
+
+    #[test]
+    fn self_intersecting_still_triangulates() {
+        let bowtie = [(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)];
+        let star = [(0.5, 0.0), (0.8, 1.0), (0.0, 0.35), (1.0, 0.35), (0.2, 1.0)];
+        for points in [bowtie.to_vec(), star.to_vec(), reversed(&star)] {
+            let n = points.len();
+            let triangles = polygon(&points).triangles();
+            assert_eq!(triangles.len(), n - 2);
+            assert!(triangles.iter().flatten().all(|&i| i < n));
+        }
+    }
+
+    #[test]
+    fn too_few_points() {
+        assert!(polygon(&[(0.0, 0.0), (1.0, 1.0)]).triangles().is_empty());
+    }
+
+    // nothing from the previous polygon is left over, whichever path either of them took
+    #[test]
+    fn reused_triangulation() {
+        let square = polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        let arrow = polygon(&[(0.0, 0.0), (1.0, 0.5), (0.0, 1.0), (0.3, 0.5)]);
+        let star = polygon(&[(0.5, 0.0), (0.8, 1.0), (0.0, 0.35), (1.0, 0.35), (0.2, 1.0)]);
+        let line = polygon(&[(0.0, 0.0), (1.0, 1.0)]);
+        let mut triangulation = Triangulation::default();
+        for polygon in [&star, &square, &arrow, &line, &square, &star, &arrow] {
+            polygon.triangulate(&mut triangulation);
+            assert_eq!(triangulation.triangles, polygon.triangles());
+        }
+    }
 }
@@ -1,24 +1,223 @@
-pub const MAX_ERROR_PER_PIXEL: f32 = 441.6729559300637; // Math::sqrt(255.0 * 255.0 * 3.0);
-pub const PER_POINT_MULTIPLIER: f32 = 1.0 / 5000000.0;
-pub const MIN_ALPHA: u8 = 8;
-pub const MAX_ALPHA: u8 = 64;
-pub const ADD_POLYGON_PROB: f32 = 1.0 / 50.0;
-pub const REMOVE_POLYGON_PROB: f32 = 1.0 / 1500.0;
-pub const REORDER_POLYGON_PROB: f32 = 1.0 / 500.0;
-pub const OFFSET_POLYGON_PROBABILITY: f32 = 1.0 / 500.0;
-pub const MOVE_POINT_PROBABILITY: f32 = 1.0 / 500.0;
-pub const REMOVE_POINT_PROBABILITY: f32 = 1.0 / 500.0;
-pub const MICRO_ADJUSTMENT_PROBABILITY: f32 = 1.0 / 100.0; // move points or shift polygons by just few pixels (useful at higher fitness levels)
-pub const CHANGE_COLOR_PROB: f32 = 1.0 / 750.0;
-pub const LIGHTEN_COLOR_PROB: f32 = 1.0 / 750.0;
-pub const DARKEN_COLOR_PROB: f32 = 1.0 / 750.0;
-pub const MOVE_POINT_MAX_DELTA: f32 = 0.1;
-pub const MICRO_ADJUSTMENT_DELTA: f32 = 0.01;
-pub const NEW_POINT_MAX_DISTANCE: f32 = 0.015;
-pub const OFFSET_POLYGON_MAGNITUDE: f32 = 0.1;
-pub const MIN_POINTS_PER_POLYGON: usize = 3;
-pub const MAX_POLYGONS_PER_IMAGE: usize = 1000;
-pub const MIN_POLYGONS_PER_IMAGE: usize = 1;
-pub const START_WITH_POLYGONS_PER_IMAGE: usize = 3;
+use std::str::FromStr;
+
+use anyhow::{ensure, Error, Result};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::{wasm_bindgen, JsError};
+
+use crate::util::to_js_error;
+
+use crate::metric::ErrorMetric;
+
+pub const MAX_ERROR_PER_PIXEL: f32 = 441.6729559300637; // Math::sqrt(255.0 * 255.0 * 3.0), for ErrorMetric::Rgb
 
 pub const DEBUG_TIMERS: bool = false;
+
+// scores every mutant a second time in full and panics when that disagrees with the incremental score
+pub const DEBUG_INCREMENTAL_EVALUATION: bool = false;
+
+// everything that can be tuned without a rebuild, owned by the Engine and handed to every mutate fn
+// pub fields get generated getters and setters on the JS side
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase", default)] // missing fields keep their default so partial json works too
+pub struct Settings {
+    pub per_point_multiplier: f32,
+    pub min_alpha: u8,
+    pub max_alpha: u8,
+    pub add_polygon_prob: f32,
+    pub remove_polygon_prob: f32,
+    pub reorder_polygon_prob: f32,
+    pub offset_polygon_probability: f32,
+    pub move_point_probability: f32,
+    pub add_point_probability: f32,
+    pub remove_point_probability: f32,
+    pub micro_adjustment_probability: f32, // move points or shift polygons by just few pixels (useful at higher fitness levels)
+    pub change_color_prob: f32,
+    pub lighten_color_prob: f32,
+    pub darken_color_prob: f32,
+    pub move_point_max_delta: f32,
+    pub micro_adjustment_delta: f32,
+    pub new_point_max_distance: f32,
+    pub offset_polygon_magnitude: f32,
+    pub min_points_per_polygon: usize,
+    pub max_points_per_polygon: usize, // 3 keeps every polygon a triangle
+    pub ellipse_share: f32, // chance of a new shape being an ellipse instead of a polygon
+    pub resize_ellipse_probability: f32,
+    pub rotate_ellipse_probability: f32,
+    pub change_fill_probability: f32, // switch a polygon between a solid color and a linear or radial gradient
+    pub stroke_share: f32, // chance of a new shape being a bezier stroke, for line art and handwriting
+    pub change_stroke_width_probability: f32,
+    pub max_polygons_per_image: usize,
+    pub min_polygons_per_image: usize,
+    pub start_with_polygons_per_image: usize,
+    pub transparent_background: bool, // new drawings start on transparent instead of the source's mean color
+    pub mutate_background: bool, // evolve the background color like a polygon color, transparent ones are never touched
+    pub batch_size: usize, // mutants evaluated per generation, the GPU renders and scores them with one submission
+    pub pyramid_levels: usize, // start at 1 / 2^(levels - 1) of the size and work up to full size, 1 disables it (only read by Engine::new)
+    pub level_up_stagnation: usize, // mutations without an improvement before moving up to the next resolution
+    pub error_metric: ErrorMetric, // changing it re-evaluates the best drawing, fitness values of different metrics aren't comparable
+    pub error_guided_placement: bool, // new shapes and moved points favour the pixels with the most error, new shapes take the source's color, reads back the per pixel error on every improvement
+    pub incremental_evaluation: bool, // only re-render and re-diff the pixels a mutation can have changed, not with ErrorMetric::Ssim
+    pub pipeline_depth: usize, // generations the GPU gets ahead of us, the next one is submitted while the last is read back, 1 waits for every one
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            per_point_multiplier: 1.0 / 5000000.0,
+            min_alpha: 8,
+            max_alpha: 64,
+            add_polygon_prob: 1.0 / 50.0,
+            remove_polygon_prob: 1.0 / 1500.0,
+            reorder_polygon_prob: 1.0 / 500.0,
+            offset_polygon_probability: 1.0 / 500.0,
+            move_point_probability: 1.0 / 500.0,
+            add_point_probability: 1.0 / 500.0,
+            remove_point_probability: 1.0 / 500.0,
+            micro_adjustment_probability: 1.0 / 100.0,
+            change_color_prob: 1.0 / 750.0,
+            lighten_color_prob: 1.0 / 750.0,
+            darken_color_prob: 1.0 / 750.0,
+            move_point_max_delta: 0.1,
+            micro_adjustment_delta: 0.01,
+            new_point_max_distance: 0.015,
+            offset_polygon_magnitude: 0.1,
+            min_points_per_polygon: 3,
+            max_points_per_polygon: 10,
+            ellipse_share: 0.25,
+            resize_ellipse_probability: 1.0 / 500.0,
+            rotate_ellipse_probability: 1.0 / 500.0,
+            change_fill_probability: 1.0 / 1000.0,
+            stroke_share: 0.0,
+            change_stroke_width_probability: 1.0 / 500.0,
+            max_polygons_per_image: 1000,
+            min_polygons_per_image: 1,
+            start_with_polygons_per_image: 3,
+            transparent_background: false,
+            mutate_background: true,
+            batch_size: 8,
+            pyramid_levels: 3,
+            level_up_stagnation: 2000,
+            error_metric: ErrorMetric::Rgb,
+            error_guided_placement: false,
+            incremental_evaluation: true,
+            pipeline_depth: 2,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Settings {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Settings {
+        Settings::default()
+    }
+
+    // throws on invalid json or settings that fail validate
+    pub fn from_json(json: String) -> Result<Settings, JsError> {
+        json.parse().map_err(to_js_error)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Expected serializable Settings.")
+    }
+}
+
+impl Settings {
+    // what the mutations rely on, anything else would panic (or loop forever) in the middle of a run
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.min_alpha <= self.max_alpha,
+            "min_alpha ({}) is above max_alpha ({})",
+            self.min_alpha,
+            self.max_alpha
+        );
+        for (name, value) in [
+            ("new_point_max_distance", self.new_point_max_distance),
+            ("move_point_max_delta", self.move_point_max_delta),
+            ("micro_adjustment_delta", self.micro_adjustment_delta),
+            ("offset_polygon_magnitude", self.offset_polygon_magnitude),
+        ] {
+            ensure!(
+                value > 0.0 && value.is_finite(),
+                "{} has to be above 0, got {}",
+                name,
+                value
+            );
+        }
+        ensure!(
+            self.min_points_per_polygon >= 3,
+            "min_points_per_polygon has to be at least 3, got {}",
+            self.min_points_per_polygon
+        );
+        ensure!(
+            self.min_points_per_polygon <= self.max_points_per_polygon,
+            "min_points_per_polygon ({}) is above max_points_per_polygon ({})",
+            self.min_points_per_polygon,
+            self.max_points_per_polygon
+        );
+        ensure!(
+            self.min_polygons_per_image <= self.max_polygons_per_image,
+            "min_polygons_per_image ({}) is above max_polygons_per_image ({})",
+            self.min_polygons_per_image,
+            self.max_polygons_per_image
+        );
+        ensure!(
+            self.start_with_polygons_per_image <= self.max_polygons_per_image,
+            "start_with_polygons_per_image ({}) is above max_polygons_per_image ({})",
+            self.start_with_polygons_per_image,
+            self.max_polygons_per_image
+        );
+        for (name, value) in [
+            ("batch_size", self.batch_size),
+            ("pipeline_depth", self.pipeline_depth),
+            ("pyramid_levels", self.pyramid_levels),
+        ] {
+            ensure!(value >= 1, "{} has to be at least 1, got {}", name, value);
+        }
+        Ok(())
+    }
+}
+
+// json like to_json, missing fields keep their defaults
+impl FromStr for Settings {
+    type Err = Error;
+
+    fn from_str(json: &str) -> Result<Settings> {
+        let settings: Settings = serde_json::from_str(json)?;
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_valid() {
+        Settings::default().validate().unwrap();
+        assert_eq!("{}".parse::<Settings>().unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn rejects_what_would_panic() {
+        assert!("{\"minAlpha\": 200, \"maxAlpha\": 100}"
+            .parse::<Settings>()
+            .is_err());
+        assert!("{\"movePointMaxDelta\": 0}".parse::<Settings>().is_err());
+        assert!("{\"minPointsPerPolygon\": 2}".parse::<Settings>().is_err());
+        assert!("{\"minPointsPerPolygon\": 8, \"maxPointsPerPolygon\": 5}"
+            .parse::<Settings>()
+            .is_err());
+        assert!(
+            "{\"startWithPolygonsPerImage\": 20, \"maxPolygonsPerImage\": 10}"
+                .parse::<Settings>()
+                .is_err()
+        );
+        assert!("{\"batchSize\": 0}".parse::<Settings>().is_err());
+        assert!("{\"pipelineDepth\": 0}".parse::<Settings>().is_err());
+        assert!("{\"pyramidLevels\": 0}".parse::<Settings>().is_err());
+        assert!("not json".parse::<Settings>().is_err());
+    }
+}
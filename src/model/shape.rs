@@ -0,0 +1,81 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::util::SeededRng;
+
+use super::{
+    color::Color,
+    ellipse::Ellipse,
+    error_map::ErrorMap,
+    point::{bounds, Point},
+    polygon::Polygon,
+    settings::Settings,
+    stroke::Stroke,
+};
+
+// what a Drawing is made of, drawn in order
+// untagged so drawings saved before there were ellipses still load, the fields tell them apart
+// the first variant that fits wins, a stroke has to come before a polygon (which would ignore its width)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Shape {
+    Stroke(Stroke),
+    Polygon(Polygon),
+    Ellipse(Ellipse),
+}
+
+impl Shape {
+    pub fn new_random(
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> Shape {
+        let kind = rng.gen::<f32>();
+        if kind < settings.ellipse_share {
+            return Shape::Ellipse(Ellipse::new_random(settings, error_map, rng));
+        }
+        if kind < settings.ellipse_share + settings.stroke_share {
+            return Shape::Stroke(Stroke::new_random(settings, error_map, rng));
+        }
+        Shape::Polygon(Polygon::new_random(settings, error_map, rng))
+    }
+
+    // an ellipse costs as much as a triangle, see Settings::per_point_multiplier
+    pub fn num_points(&self) -> usize {
+        match self {
+            Shape::Polygon(polygon) => polygon.num_points(),
+            Shape::Ellipse(_) => 3,
+            Shape::Stroke(stroke) => stroke.points.len(),
+        }
+    }
+
+    pub fn color(&self) -> &Color {
+        match self {
+            Shape::Polygon(polygon) => &polygon.color,
+            Shape::Ellipse(ellipse) => &ellipse.color,
+            Shape::Stroke(stroke) => &stroke.color,
+        }
+    }
+
+    // (min, max) of everything the shape can cover, ellipses' soft edges reach up to a pixel further
+    pub fn bounds(&self) -> (Point, Point) {
+        match self {
+            Shape::Polygon(polygon) => bounds(&polygon.points),
+            Shape::Ellipse(ellipse) => bounds(&ellipse.corners()),
+            Shape::Stroke(stroke) => bounds(&stroke.triangles()),
+        }
+    }
+
+    pub fn mutate(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> bool {
+        match self {
+            Shape::Polygon(polygon) => polygon.mutate(settings, error_map, rng),
+            Shape::Ellipse(ellipse) => ellipse.mutate(settings, error_map, rng),
+            Shape::Stroke(stroke) => stroke.mutate(settings, error_map, rng),
+        }
+    }
+}
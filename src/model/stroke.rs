@@ -0,0 +1,156 @@
+use anyhow::{ensure, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{randomf32_clamped, SeededRng};
+
+use super::{
+    color::Color,
+    error_map::{new_color, new_origin, ErrorMap},
+    point::Point,
+    settings::Settings,
+};
+
+// thinner strokes fall between the pixel centers and disappear
+pub const MIN_WIDTH: f32 = 0.001;
+
+// line segments a curve gets flattened into by to_vertices, plenty for the lengths evolution produces
+const SEGMENTS: usize = 16;
+
+// a quadratic (3 points) or cubic (4 points) bezier curve drawn as a line of the given width
+// like ellipses everything is in 0..1 coordinates, the width gets stretched along with the image
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Stroke {
+    pub points: Vec<Point>, // start, 1 or 2 control points, end
+    pub width: f32,
+    pub color: Color,
+}
+
+impl Stroke {
+    pub fn new_random(
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> Stroke {
+        let origin = new_origin(error_map, rng);
+        let d = settings.new_point_max_distance;
+        let n = if rng.gen::<bool>() { 3 } else { 4 };
+        let points = (0..n)
+            .map(|_| Point {
+                x: randomf32_clamped(origin.x - d, origin.x + d, rng).clamp(0.0, 1.0),
+                y: randomf32_clamped(origin.y - d, origin.y + d, rng).clamp(0.0, 1.0),
+            })
+            .collect();
+        Stroke {
+            points,
+            width: randomf32_clamped(MIN_WIDTH, (d * 0.5).max(MIN_WIDTH * 2.0), rng),
+            color: new_color(origin, error_map, settings, rng),
+        }
+    }
+
+    // everything else assumes 3 or 4 points, drawings loaded from json might not have them
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            (3..=4).contains(&self.points.len()),
+            "a stroke has 3 or 4 points, got {}",
+            self.points.len()
+        );
+        Ok(())
+    }
+
+    // position and derivative at t, de Casteljau style
+    fn evaluate(&self, t: f32) -> (Point, Point) {
+        let p = &self.points;
+        let u = 1.0 - t;
+        let mix = |a: Point, b: Point, t: f32| Point {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+        };
+        let (a, b) = match p.len() {
+            3 => (mix(p[0], p[1], t), mix(p[1], p[2], t)),
+            _ => {
+                let (ab, bc, cd) = (mix(p[0], p[1], t), mix(p[1], p[2], t), mix(p[2], p[3], t));
+                (mix(ab, bc, t), mix(bc, cd, t))
+            }
+        };
+        let position = Point {
+            x: a.x * u + b.x * t,
+            y: a.y * u + b.y * t,
+        };
+        // only the direction matters, the factor of 2 or 3 doesn't
+        (
+            position,
+            Point {
+                x: b.x - a.x,
+                y: b.y - a.y,
+            },
+        )
+    }
+
+    // the outline as a triangle list, quads between the left and right edge at every segment
+    pub fn triangles(&self) -> Vec<Point> {
+        let half = self.width * 0.5;
+        // in case the curve starts without a direction (first control point on top of the start)
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        let chord = f32::hypot(last.x - first.x, last.y - first.y).max(f32::EPSILON);
+        let mut normal = Point {
+            x: (first.y - last.y) / chord,
+            y: (last.x - first.x) / chord,
+        };
+        let mut edges = Vec::with_capacity(SEGMENTS + 1);
+        for i in 0..=SEGMENTS {
+            let (p, d) = self.evaluate(i as f32 / SEGMENTS as f32);
+            let length = f32::hypot(d.x, d.y);
+            if length > 0.0 {
+                normal = Point {
+                    x: -d.y / length,
+                    y: d.x / length,
+                };
+            }
+            let offset = |s: f32| Point {
+                x: p.x + normal.x * half * s,
+                y: p.y + normal.y * half * s,
+            };
+            edges.push((offset(1.0), offset(-1.0)));
+        }
+        edges
+            .windows(2)
+            .flat_map(|w| {
+                let ((l0, r0), (l1, r1)) = (w[0], w[1]);
+                [l0, r0, l1, r0, r1, l1]
+            })
+            .collect()
+    }
+
+    pub fn mutate(
+        &mut self,
+        settings: &Settings,
+        error_map: Option<&ErrorMap>,
+        rng: &mut SeededRng,
+    ) -> bool {
+        let mut mutated = false;
+        if rng.gen::<f32>() < settings.change_stroke_width_probability {
+            let d = settings.new_point_max_distance;
+            self.width =
+                randomf32_clamped(self.width - d, self.width + d, rng).clamp(MIN_WIDTH, 1.0);
+            mutated = true;
+        }
+        if rng.gen::<f32>() < settings.micro_adjustment_probability {
+            let d = settings.micro_adjustment_delta * 0.1;
+            self.width =
+                randomf32_clamped(self.width - d, self.width + d, rng).clamp(MIN_WIDTH, 1.0);
+            mutated = true;
+        }
+
+        if self.color.mutate(settings, rng) {
+            mutated = true;
+        }
+        self.points.iter_mut().for_each(|p| {
+            if p.mutate(settings, error_map, rng) {
+                mutated = true;
+            }
+        });
+
+        mutated
+    }
+}
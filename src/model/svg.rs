@@ -0,0 +1,526 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use roxmltree::Node;
+use svgtypes::{
+    Length, LengthUnit, Paint, PointsParser, SimplePathSegment, SimplifyingPathParser, Transform,
+    ViewBox,
+};
+
+use super::{
+    color::Color,
+    drawing::Drawing,
+    ellipse::{Ellipse, MIN_RADIUS},
+    fill::{Fill, MIN_RADIUS as FILL_MIN_RADIUS},
+    point::Point,
+    polygon::Polygon,
+    shape::Shape,
+    stroke::{Stroke, MIN_WIDTH},
+};
+
+// curves (and arcs, svgtypes turns them into curves) get flattened into this many line segments
+const CURVE_SEGMENTS: usize = 8;
+
+// anything inside these is only referenced from elsewhere and never drawn directly
+const NOT_RENDERED: [&str; 6] = ["defs", "clipPath", "mask", "symbol", "pattern", "marker"];
+
+impl Drawing {
+    // reads <polygon>, <path>, <rect>, <ellipse> and <circle> elements in document (z) order, fill + opacity become the Color
+    // coordinates are normalized to 0..1 using the viewBox (or width and height) of the root element
+    // every subpath of a <path> becomes its own Polygon, its quadratic and cubic segments also become a Stroke
+    // when the path is stroked, gradients are reduced to their first and last stop (other strokes and holes aren't supported)
+    pub fn from_svg(svg: &str) -> Result<Drawing> {
+        let document = roxmltree::Document::parse(svg).context("invalid svg")?;
+        let root = document.root_element();
+        if !root.has_tag_name("svg") {
+            bail!("expected an <svg> root element");
+        }
+        let view_box = view_box(root)?;
+
+        let mut shapes = vec![];
+        for node in root.descendants().filter(|n| n.is_element()) {
+            if node
+                .ancestors()
+                .any(|n| NOT_RENDERED.contains(&n.tag_name().name()))
+            {
+                continue;
+            }
+            let outlines = match node.tag_name().name() {
+                "polygon" => {
+                    vec![PointsParser::from(node.attribute("points").unwrap_or("")).collect()]
+                }
+                "rect" => vec![rect(node)?],
+                "path" => path(node)?,
+                "ellipse" | "circle" => {
+                    if let (Some(color), Some(ellipse)) =
+                        (paint(node, "fill")?, ellipse(node, &view_box)?)
+                    {
+                        shapes.push(Shape::Ellipse(Ellipse { color, ..ellipse }));
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let transform = transform(node)?;
+            let solid = paint(node, "fill")?;
+            for outline in outlines.into_iter().filter(|outline| outline.len() >= 3) {
+                let (color, fill) = match (gradient(node, &outline, &transform, &view_box)?, solid)
+                {
+                    (Some(gradient), _) => gradient,
+                    (None, Some(color)) => (color, Fill::Solid),
+                    (None, None) => continue,
+                };
+                let points = outline
+                    .into_iter()
+                    .map(|point| to_unit(point, &transform, &view_box))
+                    .collect();
+                shapes.push(Shape::Polygon(Polygon {
+                    points,
+                    color,
+                    fill,
+                }));
+            }
+            // strokes get painted on top of the fill
+            if node.has_tag_name("path") {
+                if let Some(color) = paint(node, "stroke")? {
+                    shapes.extend(strokes(node, &transform, &view_box, color)?);
+                }
+            }
+        }
+
+        // an opaque shape covering everything at the bottom is a background, like the <rect> to_svg writes
+        let background = match shapes.first() {
+            Some(Shape::Polygon(first)) if first.color.a == 255 && covers_everything(first) => {
+                shapes.remove(0).color().to_owned()
+            }
+            _ => Color::WHITE, // what browsers show behind an svg
+        };
+        if shapes.is_empty() {
+            bail!("no filled <polygon>, <path>, <rect>, <ellipse> or <circle> elements found");
+        }
+        Ok(Drawing {
+            shapes,
+            is_dirty: true,
+            fitness: 0.0,
+            background,
+        })
+    }
+}
+
+// the 4 corners of the viewBox, in any order
+fn covers_everything(polygon: &Polygon) -> bool {
+    polygon.points.len() == 4
+        && [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .iter()
+            .all(|&(x, y)| polygon.points.iter().any(|p| p.x == x && p.y == y))
+}
+
+fn view_box(root: Node) -> Result<ViewBox> {
+    if let Some(view_box) = root.attribute("viewBox") {
+        return ViewBox::from_str(view_box).map_err(|e| anyhow!("invalid viewBox: {}", e));
+    }
+    // no viewBox -> user space is the viewport, units are ignored
+    let width = length(root, "width")?.ok_or_else(|| anyhow!("svg has no viewBox or width"))?;
+    let height = length(root, "height")?.ok_or_else(|| anyhow!("svg has no viewBox or height"))?;
+    Ok(ViewBox::new(0.0, 0.0, width, height))
+}
+
+fn length(node: Node, name: &str) -> Result<Option<f64>> {
+    node.attribute(name)
+        .map(|value| {
+            Length::from_str(value)
+                .map(|length| length.number)
+                .map_err(|e| anyhow!("invalid {} {:?}: {}", name, value, e))
+        })
+        .transpose()
+}
+
+fn rect(node: Node) -> Result<Vec<(f64, f64)>> {
+    let x = length(node, "x")?.unwrap_or(0.0);
+    let y = length(node, "y")?.unwrap_or(0.0);
+    let w = length(node, "width")?.unwrap_or(0.0);
+    let h = length(node, "height")?.unwrap_or(0.0);
+    if w <= 0.0 || h <= 0.0 {
+        return Ok(vec![]); // not rendered according to the spec
+    }
+    // rx / ry are ignored, rounded corners would need a lot of points for very little difference
+    Ok(vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)])
+}
+
+// the unit circle scaled by the radii, transformed and normalized is still an ellipse, its axes
+// are the eigenvectors of M * M^T (M being the 2x2 part of all of that), the radii the roots of the eigenvalues
+// color is left transparent for the caller to fill in
+fn ellipse(node: Node, view_box: &ViewBox) -> Result<Option<Ellipse>> {
+    let cx = length(node, "cx")?.unwrap_or(0.0);
+    let cy = length(node, "cy")?.unwrap_or(0.0);
+    let (rx, ry) = match node.tag_name().name() {
+        "circle" => {
+            let r = length(node, "r")?.unwrap_or(0.0);
+            (r, r)
+        }
+        _ => {
+            // a missing rx / ry is the other one (auto)
+            let rx = length(node, "rx")?;
+            let ry = length(node, "ry")?;
+            (rx.or(ry).unwrap_or(0.0), ry.or(rx).unwrap_or(0.0))
+        }
+    };
+    if rx <= 0.0 || ry <= 0.0 {
+        return Ok(None); // not rendered according to the spec
+    }
+
+    let t = transform(node)?;
+    let m = [
+        [t.a * rx / view_box.w, t.c * ry / view_box.w],
+        [t.b * rx / view_box.h, t.d * ry / view_box.h],
+    ];
+    let e00 = m[0][0] * m[0][0] + m[0][1] * m[0][1];
+    let e11 = m[1][0] * m[1][0] + m[1][1] * m[1][1];
+    let e01 = m[0][0] * m[1][0] + m[0][1] * m[1][1];
+    let mean = (e00 + e11) * 0.5;
+    let spread = f64::hypot((e00 - e11) * 0.5, e01);
+    let rotation = 0.5 * f64::atan2(2.0 * e01, e00 - e11);
+
+    Ok(Some(Ellipse {
+        center: to_unit((cx, cy), &t, view_box),
+        rx: (((mean + spread).sqrt()) as f32).clamp(MIN_RADIUS, 1.0),
+        ry: (((mean - spread).max(0.0).sqrt()) as f32).clamp(MIN_RADIUS, 1.0),
+        rotation: (rotation as f32).rem_euclid(std::f32::consts::PI),
+        color: Color::TRANSPARENT,
+    }))
+}
+
+// the quadratic and cubic segments of a stroked path, anything else it's made of gets ignored
+// a non-uniform transform would make the width vary along the curve, it gets the average scale instead
+fn strokes(node: Node, t: &Transform, view_box: &ViewBox, color: Color) -> Result<Vec<Shape>> {
+    let width = length(node, "stroke-width")?.unwrap_or(1.0);
+    let scale = ((t.a * t.d - t.b * t.c) / (view_box.w * view_box.h))
+        .abs()
+        .sqrt();
+    let width = ((width * scale) as f32).clamp(MIN_WIDTH, 1.0);
+
+    let mut strokes = vec![];
+    let mut current = (0.0, 0.0);
+    let mut start = current;
+    for segment in SimplifyingPathParser::from(node.attribute("d").unwrap_or("")) {
+        let segment = segment.map_err(|e| anyhow!("invalid path data: {}", e))?;
+        let points = match segment {
+            SimplePathSegment::MoveTo { x, y } => {
+                start = (x, y);
+                current = (x, y);
+                continue;
+            }
+            SimplePathSegment::LineTo { x, y } => {
+                current = (x, y);
+                continue;
+            }
+            SimplePathSegment::ClosePath => {
+                current = start;
+                continue;
+            }
+            SimplePathSegment::Quadratic { x1, y1, x, y } => vec![current, (x1, y1), (x, y)],
+            SimplePathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => vec![current, (x1, y1), (x2, y2), (x, y)],
+        };
+        current = *points.last().unwrap();
+        strokes.push(Shape::Stroke(Stroke {
+            points: points
+                .into_iter()
+                .map(|point| to_unit(point, t, view_box))
+                .collect(),
+            width,
+            color,
+        }));
+    }
+    Ok(strokes)
+}
+
+// user space -> viewport -> 0..1, anything outside the viewBox gets clamped
+fn to_unit(point: (f64, f64), t: &Transform, view_box: &ViewBox) -> Point {
+    let Point { x, y } = to_unit_unclamped(point, t, view_box);
+    Point {
+        x: x.clamp(0.0, 1.0),
+        y: y.clamp(0.0, 1.0),
+    }
+}
+
+fn to_unit_unclamped((x, y): (f64, f64), t: &Transform, view_box: &ViewBox) -> Point {
+    let tx = t.a * x + t.c * y + t.e;
+    let ty = t.b * x + t.d * y + t.f;
+    Point {
+        x: ((tx - view_box.x) / view_box.w) as f32,
+        y: ((ty - view_box.y) / view_box.h) as f32,
+    }
+}
+
+// the polygon's color and Fill for a fill="url(#id)" pointing at a <linearGradient> or <radialGradient>
+// objectBoundingBox coordinates (the default) are relative to the outline's bounds in user space,
+// focal points and spread methods are ignored and a stretched radial gradient gets the average radius
+fn gradient(
+    node: Node,
+    outline: &[(f64, f64)],
+    t: &Transform,
+    view_box: &ViewBox,
+) -> Result<Option<(Color, Fill)>> {
+    let Some(Ok(Paint::FuncIRI(id, _))) = inherited(node, "fill").map(Paint::from_str) else {
+        return Ok(None);
+    };
+    let document = node.document();
+    let by_id = |id: &str| {
+        document
+            .descendants()
+            .find(|n| n.attribute("id") == Some(id))
+    };
+    let Some(gradient) = by_id(id) else {
+        return Ok(None);
+    };
+    let linear = match gradient.tag_name().name() {
+        "linearGradient" => true,
+        "radialGradient" => false,
+        _ => return Ok(None),
+    };
+
+    // a gradient without stops uses the ones of the gradient it references
+    let mut source = Some(gradient);
+    let stops = loop {
+        let Some(current) = source else {
+            return Ok(None);
+        };
+        let stops: Vec<Node> = current
+            .children()
+            .filter(|n| n.has_tag_name("stop"))
+            .collect();
+        if !stops.is_empty() {
+            break stops;
+        }
+        source = current
+            .attribute("href")
+            .or_else(|| current.attribute(("http://www.w3.org/1999/xlink", "href")))
+            .and_then(|href| by_id(href.trim_start_matches('#')));
+    };
+    let stop_color = |stop: Node| -> Result<Color> {
+        let color = match property(stop, "stop-color") {
+            Some(value) => svgtypes::Color::from_str(value)
+                .map_err(|e| anyhow!("invalid stop-color {:?}: {}", value, e))?,
+            None => svgtypes::Color::black(),
+        };
+        let alpha = opacity(property(stop, "stop-opacity"))? * alpha(node, "fill")?;
+        Ok(to_color(color, alpha))
+    };
+    let from = stop_color(stops[0])?;
+    let to = stop_color(stops[stops.len() - 1])?;
+
+    let bounding_box = gradient.attribute("gradientUnits") != Some("userSpaceOnUse");
+    let (min, max) = outline.iter().fold(
+        ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN)),
+        |(min, max), &(x, y)| ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y))),
+    );
+    let size = (max.0 - min.0, max.1 - min.1);
+    // percentages are of the bounding box or the viewport, plain numbers are fractions of the bounding box or user space
+    let coordinate = |name: &str, default: f64, extent: f64| -> Result<f64> {
+        let Some(value) = gradient.attribute(name) else {
+            return Ok(default * if bounding_box { 1.0 } else { extent });
+        };
+        let length =
+            Length::from_str(value).map_err(|e| anyhow!("invalid {} {:?}: {}", name, value, e))?;
+        Ok(match (length.unit, bounding_box) {
+            (LengthUnit::Percent, true) => length.number / 100.0,
+            (LengthUnit::Percent, false) => length.number / 100.0 * extent,
+            _ => length.number,
+        })
+    };
+    let gradient_transform = match gradient.attribute("gradientTransform") {
+        Some(value) => Transform::from_str(value)
+            .map_err(|e| anyhow!("invalid gradientTransform {:?}: {}", value, e))?,
+        None => Transform::default(),
+    };
+    // gradient space -> user space
+    let units = match bounding_box {
+        true => Transform::new(size.0, 0.0, 0.0, size.1, min.0, min.1),
+        false => Transform::default(),
+    };
+    let to_user = multiply(t, &multiply(&units, &gradient_transform));
+    let point = |x: f64, y: f64| to_unit_unclamped((x, y), &to_user, view_box);
+
+    let fill = if linear {
+        Fill::Linear {
+            start: point(
+                coordinate("x1", 0.0, view_box.w)?,
+                coordinate("y1", 0.0, view_box.h)?,
+            ),
+            end: point(
+                coordinate("x2", 1.0, view_box.w)?,
+                coordinate("y2", 0.0, view_box.h)?,
+            ),
+            color: to,
+        }
+    } else {
+        let diagonal = f64::hypot(view_box.w, view_box.h) / std::f64::consts::SQRT_2;
+        let r = coordinate("r", 0.5, diagonal)?;
+        let scale = ((to_user.a * to_user.d - to_user.b * to_user.c) / (view_box.w * view_box.h))
+            .abs()
+            .sqrt();
+        Fill::Radial {
+            center: point(
+                coordinate("cx", 0.5, view_box.w)?,
+                coordinate("cy", 0.5, view_box.h)?,
+            ),
+            radius: ((r * scale) as f32).clamp(FILL_MIN_RADIUS, 1.0),
+            color: to,
+        }
+    };
+    Ok(Some((from, fill)))
+}
+
+fn path(node: Node) -> Result<Vec<Vec<(f64, f64)>>> {
+    let mut subpaths = vec![];
+    let mut current: Vec<(f64, f64)> = vec![];
+    for segment in SimplifyingPathParser::from(node.attribute("d").unwrap_or("")) {
+        let segment = segment.map_err(|e| anyhow!("invalid path data: {}", e))?;
+        let (x0, y0) = current.last().copied().unwrap_or((0.0, 0.0));
+        match segment {
+            SimplePathSegment::MoveTo { x, y } => {
+                subpaths.push(std::mem::take(&mut current));
+                current.push((x, y));
+            }
+            SimplePathSegment::LineTo { x, y } => current.push((x, y)),
+            SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                current.extend((1..=CURVE_SEGMENTS).map(|i| {
+                    let t = i as f64 / CURVE_SEGMENTS as f64;
+                    let u = 1.0 - t;
+                    (
+                        u * u * x0 + 2.0 * u * t * x1 + t * t * x,
+                        u * u * y0 + 2.0 * u * t * y1 + t * t * y,
+                    )
+                }));
+            }
+            SimplePathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                current.extend((1..=CURVE_SEGMENTS).map(|i| {
+                    let t = i as f64 / CURVE_SEGMENTS as f64;
+                    let u = 1.0 - t;
+                    (
+                        u * u * u * x0
+                            + 3.0 * u * u * t * x1
+                            + 3.0 * u * t * t * x2
+                            + t * t * t * x,
+                        u * u * u * y0
+                            + 3.0 * u * u * t * y1
+                            + 3.0 * u * t * t * y2
+                            + t * t * t * y,
+                    )
+                }));
+            }
+            SimplePathSegment::ClosePath => subpaths.push(std::mem::take(&mut current)),
+        }
+    }
+    subpaths.push(current);
+    Ok(subpaths)
+}
+
+// presentation attribute or style property, style wins
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    });
+    from_style.or_else(|| node.attribute(name))
+}
+
+// first value found on the element or its ancestors
+fn inherited<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.ancestors()
+        .filter_map(|n| property(n, name))
+        .find(|value| *value != "inherit")
+}
+
+fn opacity(value: Option<&str>) -> Result<f64> {
+    match value {
+        Some(value) => Ok(f64::from_str(value.trim_end_matches('%'))
+            .map(|o| if value.ends_with('%') { o / 100.0 } else { o })
+            .map_err(|e| anyhow!("invalid opacity {:?}: {}", value, e))?
+            .clamp(0.0, 1.0)),
+        None => Ok(1.0),
+    }
+}
+
+// fill or stroke, None when the element isn't painted with it (none, or a gradient/pattern without a fallback color)
+// unset fills are black, unset strokes none
+fn paint(node: Node, name: &str) -> Result<Option<Color>> {
+    let paint = match inherited(node, name) {
+        Some(value) => {
+            Paint::from_str(value).map_err(|e| anyhow!("invalid {} {:?}: {}", name, value, e))?
+        }
+        None if name == "fill" => Paint::Color(svgtypes::Color::black()),
+        None => Paint::None,
+    };
+    let color = match paint {
+        Paint::Color(color) => color,
+        Paint::CurrentColor => match inherited(node, "color") {
+            Some(value) => svgtypes::Color::from_str(value)
+                .map_err(|e| anyhow!("invalid color {:?}: {}", value, e))?,
+            None => svgtypes::Color::black(),
+        },
+        Paint::FuncIRI(_, Some(svgtypes::PaintFallback::Color(color))) => color,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(to_color(color, alpha(node, name)?)))
+}
+
+// fill-opacity / stroke-opacity are inherited, opacity applies to the whole group so it multiplies down the tree
+fn alpha(node: Node, name: &str) -> Result<f64> {
+    let mut alpha = opacity(inherited(node, &format!("{}-opacity", name)))?;
+    for n in node.ancestors() {
+        alpha *= opacity(property(n, "opacity"))?;
+    }
+    Ok(alpha)
+}
+
+fn to_color(color: svgtypes::Color, alpha: f64) -> Color {
+    Color {
+        r: color.red,
+        g: color.green,
+        b: color.blue,
+        a: (color.alpha as f64 * alpha).round() as u8,
+    }
+}
+
+// the element's transform combined with the transforms of all its ancestors
+fn transform(node: Node) -> Result<Transform> {
+    let mut ts = Transform::default();
+    for n in node.ancestors() {
+        if let Some(value) = n.attribute("transform") {
+            let parent = Transform::from_str(value)
+                .map_err(|e| anyhow!("invalid transform {:?}: {}", value, e))?;
+            ts = multiply(&parent, &ts);
+        }
+    }
+    Ok(ts)
+}
+
+// a * b, i.e. b gets applied first
+fn multiply(a: &Transform, b: &Transform) -> Transform {
+    Transform::new(
+        a.a * b.a + a.c * b.b,
+        a.b * b.a + a.d * b.b,
+        a.a * b.c + a.c * b.d,
+        a.b * b.c + a.d * b.d,
+        a.a * b.e + a.c * b.f + a.e,
+        a.b * b.e + a.d * b.f + a.f,
+    )
+}
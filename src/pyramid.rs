@@ -0,0 +1,170 @@
+use anyhow::{ensure, Result};
+use image::imageops::{resize, FilterType};
+use image::{GrayImage, RgbaImage};
+
+// levels stop before either side would get smaller than this
+const MIN_LEVEL_SIZE: usize = 32;
+
+pub(crate) struct Level {
+    pub width: usize,
+    pub height: usize,
+    pub source_bytes: Vec<u8>,
+    pub mask: Option<Vec<u8>>, // one weight per pixel, 0 = ignored, 255 = full weight
+    pub weight: f32, // sum of the normalized mask (width * height without one), replaces the pixel count in fitness
+}
+
+// the source image at decreasing resolutions, level 0 is the full size and every next level halves it
+pub(crate) struct Pyramid {
+    levels: Vec<Level>,
+}
+
+impl Pyramid {
+    // mask is either one byte per pixel or RGBA8 (red channel), see set_mask
+    pub fn new(
+        source_bytes: Vec<u8>,
+        mask: Option<Vec<u8>>,
+        width: usize,
+        height: usize,
+        max_levels: usize,
+    ) -> Result<Self> {
+        ensure!(
+            width > 0 && height > 0 && source_bytes.len() == width * height * 4,
+            "expected {}x{} RGBA8 pixels ({} bytes), got {} bytes",
+            width,
+            height,
+            width * height * 4,
+            source_bytes.len()
+        );
+        let mut levels = vec![Level {
+            width,
+            height,
+            source_bytes,
+            mask: None,
+            weight: (width * height) as f32,
+        }];
+        while levels.len() < max_levels {
+            let previous = levels.last().unwrap();
+            let (w, h) = (previous.width / 2, previous.height / 2);
+            if w < MIN_LEVEL_SIZE || h < MIN_LEVEL_SIZE {
+                break;
+            }
+            levels.push(Level {
+                width: w,
+                height: h,
+                source_bytes: scale(
+                    &previous.source_bytes,
+                    previous.width,
+                    previous.height,
+                    w,
+                    h,
+                    FilterType::Triangle,
+                ),
+                mask: None,
+                weight: (w * h) as f32,
+            });
+        }
+        let mut pyramid = Pyramid { levels };
+        pyramid.set_mask(mask)?;
+        Ok(pyramid)
+    }
+
+    // replaces the mask of every level, None weighs every pixel the same
+    // an error (and the old mask kept) if it's not the source's size or all black, which would leave nothing to score
+    pub fn set_mask(&mut self, mask: Option<Vec<u8>>) -> Result<()> {
+        let (width, height) = (self.levels[0].width, self.levels[0].height);
+        let mask = match mask {
+            Some(bytes) if bytes.len() == width * height * 4 => {
+                Some(bytes.chunks_exact(4).map(|p| p[0]).collect::<Vec<u8>>())
+            }
+            Some(bytes) => {
+                ensure!(
+                    bytes.len() == width * height,
+                    "expected a grayscale or RGBA8 mask of {}x{}, got {} bytes",
+                    width,
+                    height,
+                    bytes.len()
+                );
+                Some(bytes)
+            }
+            None => None,
+        };
+        if let Some(mask) = &mask {
+            ensure!(mask.iter().any(|&w| w > 0), "the mask is all black");
+        }
+
+        for level in &mut self.levels {
+            level.mask = mask.as_ref().map(|bytes| match level.width == width {
+                true => bytes.clone(),
+                false => scale_mask(bytes, width, height, level.width, level.height),
+            });
+            level.weight = match &level.mask {
+                Some(bytes) => bytes.iter().map(|&w| w as f32 / 255.0).sum(),
+                None => (level.width * level.height) as f32,
+            };
+        }
+        Ok(())
+    }
+
+    pub fn level(&self, level: usize) -> &Level {
+        &self.levels[level]
+    }
+
+    pub fn coarsest(&self) -> usize {
+        self.levels.len() - 1
+    }
+}
+
+// RGBA8 pixels from one size to another
+pub(crate) fn scale(
+    bytes: &[u8],
+    width: usize,
+    height: usize,
+    new_width: usize,
+    new_height: usize,
+    filter: FilterType,
+) -> Vec<u8> {
+    let image = RgbaImage::from_raw(width as u32, height as u32, bytes.to_vec())
+        .expect("Expected width * height RGBA8 pixels.");
+    resize(&image, new_width as u32, new_height as u32, filter).into_raw()
+}
+
+fn scale_mask(
+    bytes: &[u8],
+    width: usize,
+    height: usize,
+    new_width: usize,
+    new_height: usize,
+) -> Vec<u8> {
+    let image = GrayImage::from_raw(width as u32, height as u32, bytes.to_vec())
+        .expect("Expected a width * height mask.");
+    resize(
+        &image,
+        new_width as u32,
+        new_height as u32,
+        FilterType::Triangle,
+    )
+    .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyramid(mask: Option<Vec<u8>>) -> Result<Pyramid> {
+        Pyramid::new(vec![128; 64 * 64 * 4], mask, 64, 64, 3)
+    }
+
+    #[test]
+    fn masks_have_to_fit_and_weigh_something() {
+        assert!(pyramid(Some(vec![255; 64 * 64])).is_ok());
+        assert!(pyramid(Some(vec![255; 64 * 64 * 4])).is_ok());
+        assert!(pyramid(Some(vec![255; 64 * 63])).is_err());
+        assert!(pyramid(Some(vec![0; 64 * 64])).is_err());
+        assert!(Pyramid::new(vec![128; 64 * 63 * 4], None, 64, 64, 3).is_err());
+
+        // a rejected mask leaves the current one in place
+        let mut pyramid = pyramid(Some(vec![255; 64 * 64])).unwrap();
+        assert!(pyramid.set_mask(Some(vec![0; 64 * 64])).is_err());
+        assert_eq!(pyramid.level(0).weight, (64 * 64) as f32);
+    }
+}
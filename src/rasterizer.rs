@@ -0,0 +1,425 @@
+use crate::backend::Rect;
+use crate::metric::{ssim_errors, ErrorMetric};
+use crate::Vertex;
+
+// GPUs snap vertex positions to a fixed point grid before rasterizing, 8 bits of subpixel precision is what most use
+const SUBPIXEL_STEPS: f64 = 256.0;
+
+// Software version of the render pipeline in Engine::draw.
+// Takes the output of Drawing::to_vertices (a TriangleList in clip space) and returns unpadded, premultiplied RGBA8 pixels.
+pub fn rasterize(vertices: &[Vertex], width: usize, height: usize) -> Vec<u8> {
+    rasterize_scissored(vertices, width, height, Rect::full(width, height))
+}
+
+// only the pixels inside scissor get drawn, like set_scissor_rect, the rest stays transparent
+pub fn rasterize_scissored(
+    vertices: &[Vertex],
+    width: usize,
+    height: usize,
+    scissor: Rect,
+) -> Vec<u8> {
+    // LoadOp::Clear(wgpu::Color::TRANSPARENT)
+    let mut pixels = vec![0u8; width * height * 4];
+    rasterize_scissored_into(&mut pixels, vertices, width, height, scissor);
+    pixels
+}
+
+// same, but into pixels left over from an earlier call, only the ones inside scissor are cleared and drawn
+pub fn rasterize_scissored_into(
+    pixels: &mut [u8],
+    vertices: &[Vertex],
+    width: usize,
+    height: usize,
+    scissor: Rect,
+) {
+    assert_eq!(pixels.len(), width * height * 4);
+    if scissor.is_empty() {
+        return;
+    }
+
+    for y in scissor.y..scissor.y + scissor.height {
+        let row = (y * width + scissor.x) * 4;
+        pixels[row..row + scissor.width * 4].fill(0);
+    }
+    for triangle in vertices.chunks_exact(3) {
+        draw_triangle(pixels, width, height, scissor, triangle);
+    }
+}
+
+// CPU version of error.compute2.wgsl, the metric's distance between the RGB channels for every pixel
+// weighted by the mask (one byte per pixel) if there is one
+pub fn calculate_error(
+    source_bytes: &[u8],
+    drawing_bytes: &[u8],
+    mask: Option<&[u8]>,
+    width: usize,
+    metric: ErrorMetric,
+) -> Vec<f32> {
+    assert_eq!(source_bytes.len(), drawing_bytes.len());
+    let mut errors = match metric {
+        ErrorMetric::Ssim => ssim_errors(source_bytes, drawing_bytes, width),
+        _ => per_pixel_errors(source_bytes, drawing_bytes, metric),
+    };
+    if let Some(mask) = mask {
+        for (error, &weight) in errors.iter_mut().zip(mask) {
+            *error *= weight as f32 / 255.0;
+        }
+    }
+    errors
+}
+
+// sum of calculate_error over the pixels inside rect, not for Ssim (its windows reach outside)
+pub fn calculate_error_in(
+    source_bytes: &[u8],
+    drawing_bytes: &[u8],
+    mask: Option<&[u8]>,
+    width: usize,
+    metric: ErrorMetric,
+    rect: Rect,
+) -> f32 {
+    assert!(metric != ErrorMetric::Ssim);
+    rect.indices(width)
+        .map(|i| {
+            let error = metric.pixel_error(
+                &source_bytes[i * 4..i * 4 + 4],
+                &drawing_bytes[i * 4..i * 4 + 4],
+            );
+            match mask {
+                Some(mask) => error * (mask[i] as f32 / 255.0),
+                None => error,
+            }
+        })
+        .sum()
+}
+
+fn per_pixel_errors(source_bytes: &[u8], drawing_bytes: &[u8], metric: ErrorMetric) -> Vec<f32> {
+    source_bytes
+        .chunks_exact(4)
+        .zip(drawing_bytes.chunks_exact(4))
+        .map(|(a, b)| metric.pixel_error(a, b))
+        .collect()
+}
+
+fn draw_triangle(
+    pixels: &mut [u8],
+    width: usize,
+    height: usize,
+    scissor: Rect,
+    triangle: &[Vertex],
+) {
+    // clip space -> framebuffer coordinates, y points down
+    let to_screen = |v: &Vertex| -> (f64, f64) {
+        let x = (v.position[0] as f64 + 1.0) * 0.5 * width as f64;
+        let y = (1.0 - v.position[1] as f64) * 0.5 * height as f64;
+        (
+            (x * SUBPIXEL_STEPS).round() / SUBPIXEL_STEPS,
+            (y * SUBPIXEL_STEPS).round() / SUBPIXEL_STEPS,
+        )
+    };
+
+    let mut v = [&triangle[0], &triangle[1], &triangle[2]];
+    let mut p = [to_screen(v[0]), to_screen(v[1]), to_screen(v[2])];
+
+    let mut area = edge(p[0], p[1], p[2]);
+    if area == 0.0 {
+        return; // degenerate, nothing gets rasterized
+    }
+    if area < 0.0 {
+        // cull_mode is None so both windings are drawn, flip it so the inside is always positive
+        v.swap(1, 2);
+        p.swap(1, 2);
+        area = -area;
+    }
+
+    let min_x = (p
+        .iter()
+        .map(|p| p.0)
+        .fold(f64::MAX, f64::min)
+        .floor()
+        .max(0.0) as usize)
+        .max(scissor.x);
+    let min_y = (p
+        .iter()
+        .map(|p| p.1)
+        .fold(f64::MAX, f64::min)
+        .floor()
+        .max(0.0) as usize)
+        .max(scissor.y);
+    let max_x = (p.iter().map(|p| p.0).fold(f64::MIN, f64::max).ceil() as usize)
+        .min(width)
+        .min(scissor.x + scissor.width);
+    let max_y = (p.iter().map(|p| p.1).fold(f64::MIN, f64::max).ceil() as usize)
+        .min(height)
+        .min(scissor.y + scissor.height);
+
+    let edges = [(p[1], p[2]), (p[2], p[0]), (p[0], p[1])];
+    let top_left = edges.map(|(a, b)| is_top_left(a, b));
+
+    // what dpdx / dpdy give the fragment shader, constant across the triangle since the weights are affine
+    let derivatives = |attribute: fn(&Vertex) -> [f32; 4]| -> ([f32; 2], [f32; 2]) {
+        let (mut dx, mut dy) = ([0.0f32; 2], [0.0f32; 2]);
+        for i in 0..3 {
+            let (a, b) = edges[i];
+            let (wx, wy) = ((a.1 - b.1) / area, (b.0 - a.0) / area);
+            for c in 0..2 {
+                dx[c] += attribute(v[i])[c] * wx as f32;
+                dy[c] += attribute(v[i])[c] * wy as f32;
+            }
+        }
+        (dx, dy)
+    };
+    let shape_derivatives = derivatives(|v| v.shape);
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            // sample at the pixel center
+            let center = (x as f64 + 0.5, y as f64 + 0.5);
+            let mut weights = [0.0f32; 3];
+            let mut inside = true;
+            for i in 0..3 {
+                let e = edge(edges[i].0, edges[i].1, center);
+                if e < 0.0 || (e == 0.0 && !top_left[i]) {
+                    inside = false;
+                    break;
+                }
+                weights[i] = (e / area) as f32;
+            }
+            if !inside {
+                continue;
+            }
+
+            let fragment = interpolate(&v, weights);
+            let index = (y * width + x) * 4;
+            blend(
+                &mut pixels[index..index + 4],
+                fs_main(&fragment, shape_derivatives),
+            );
+        }
+    }
+}
+
+// twice the signed area of (a, b, p), positive when p is on the inside of a triangle wound like (a, b, ...)
+fn edge(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+// top-left fill rule, pixels exactly on a shared edge belong to only one of the triangles
+fn is_top_left(a: (f64, f64), b: (f64, f64)) -> bool {
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+    (dy == 0.0 && dx > 0.0) || dy < 0.0
+}
+
+// what the rasterizer hands over to the fragment shader, barycentric interpolation of every attribute
+fn interpolate(v: &[&Vertex; 3], w: [f32; 3]) -> Vertex {
+    let mix = |a: [f32; 4], b: [f32; 4], c: [f32; 4]| -> [f32; 4] {
+        [0, 1, 2, 3].map(|i| a[i] * w[0] + b[i] * w[1] + c[i] * w[2])
+    };
+    Vertex {
+        position: mix(v[0].position, v[1].position, v[2].position),
+        color: mix(v[0].color, v[1].color, v[2].color),
+        shape: mix(v[0].shape, v[1].shape, v[2].shape),
+        gradient: mix(v[0].gradient, v[1].gradient, v[2].gradient),
+        gradient_color: mix(
+            v[0].gradient_color,
+            v[1].gradient_color,
+            v[2].gradient_color,
+        ),
+    }
+}
+
+// mirrors fs_main in shader.wgsl
+fn fs_main(fragment: &Vertex, (dx, dy): ([f32; 2], [f32; 2])) -> [f32; 4] {
+    let color = match fragment.shape[3] {
+        0.0 => fragment.color,
+        _ => gradient_color(fragment),
+    };
+    if fragment.shape[2] == 0.0 {
+        return color;
+    }
+    let [r, g, b, a] = color;
+    let uv = [fragment.shape[0], fragment.shape[1]];
+    [r, g, b, a * ellipse_coverage(uv, dx, dy)]
+}
+
+fn gradient_color(fragment: &Vertex) -> [f32; 4] {
+    let [px, py] = [fragment.shape[0], fragment.shape[1]];
+    let g = fragment.gradient;
+    // the kind is interpolated like every other attribute, it isn't exactly 1 or 2 anymore
+    let t = if fragment.shape[3] < 1.5 {
+        let (dx, dy) = (g[2] - g[0], g[3] - g[1]);
+        ((px - g[0]) * dx + (py - g[1]) * dy) / (dx * dx + dy * dy).max(1e-12)
+    } else {
+        f32::hypot(px - g[0], py - g[1]) / g[2].max(1e-6)
+    };
+    let t = t.clamp(0.0, 1.0);
+    let (from, to) = (fragment.color, fragment.gradient_color);
+    let a = from[3] + (to[3] - from[3]) * t;
+    let [r, g, b] = [0, 1, 2]
+        .map(|i| (from[i] * from[3] + (to[i] * to[3] - from[i] * from[3]) * t) / a.max(1e-6));
+    [r, g, b, a]
+}
+
+fn ellipse_coverage(uv: [f32; 2], dx: [f32; 2], dy: [f32; 2]) -> f32 {
+    let r = f32::hypot(uv[0], uv[1]);
+    if r < 0.5 {
+        return 1.0;
+    }
+    let gradient = [
+        (uv[0] * dx[0] + uv[1] * dx[1]) / r,
+        (uv[0] * dy[0] + uv[1] * dy[1]) / r,
+    ];
+    let d = (r - 1.0) / f32::hypot(gradient[0], gradient[1]).max(1e-6);
+    (0.5 - d).clamp(0.0, 1.0)
+}
+
+// mirrors the BlendState of the render pipeline, the target ends up premultiplied:
+// color = src * src_alpha + dst * (1 - src_alpha), alpha = src_alpha + dst_alpha * (1 - src_alpha)
+// blending happens in float and is rounded once, software GL drivers (llvmpipe) round each term separately
+// so they can end up a LSB brighter per layer
+fn blend(dst: &mut [u8], src: [f32; 4]) {
+    let a = src[3].clamp(0.0, 1.0);
+    for i in 0..3 {
+        let d = dst[i] as f32 / 255.0;
+        dst[i] = to_unorm(src[i] * a + d * (1.0 - a));
+    }
+    dst[3] = to_unorm(a + dst[3] as f32 / 255.0 * (1.0 - a));
+}
+
+// float -> Rgba8Unorm conversion of the render target
+fn to_unorm(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::settings::MAX_ERROR_PER_PIXEL;
+
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 0.5];
+
+    // solid polygon vertex at clip space x, y
+    fn vertex(x: f32, y: f32, color: [f32; 4]) -> Vertex {
+        Vertex {
+            position: [x, y, 0.0, 1.0],
+            color,
+            shape: [0.0; 4],
+            gradient: [0.0; 4],
+            gradient_color: [0.0; 4],
+        }
+    }
+
+    // two triangles sharing the diagonal from (x0, y0) to (x1, y1)
+    fn quad(x0: f32, y0: f32, x1: f32, y1: f32, color: [f32; 4]) -> Vec<Vertex> {
+        [(x0, y0), (x1, y0), (x1, y1), (x0, y0), (x1, y1), (x0, y1)]
+            .map(|(x, y)| vertex(x, y, color))
+            .to_vec()
+    }
+
+    #[test]
+    fn shared_edges_are_blended_once() {
+        // 5x5: the diagonals and the vertical edge at x = 0 go right through pixel centers
+        let mut vertices = quad(-1.0, -1.0, 0.0, 1.0, RED);
+        vertices.extend(quad(0.0, -1.0, 1.0, 1.0, RED));
+        let pixels = rasterize(&vertices, 5, 5);
+        for pixel in pixels.chunks_exact(4) {
+            assert_eq!(pixel, [128, 0, 0, 128]);
+        }
+    }
+
+    #[test]
+    fn both_windings_are_drawn() {
+        let mut vertices = quad(-1.0, -1.0, 1.0, 1.0, RED);
+        vertices.reverse();
+        let pixels = rasterize(&vertices, 4, 4);
+        assert!(pixels.chunks_exact(4).all(|p| p == [128, 0, 0, 128]));
+    }
+
+    #[test]
+    fn blend_over_transparent_is_premultiplied() {
+        let mut dst = [0u8; 4];
+        blend(&mut dst, [0.8, 0.4, 0.0, 0.5]);
+        assert_eq!(dst, [102, 51, 0, 128]);
+    }
+
+    #[test]
+    fn blend_over_opaque() {
+        let mut dst = [0, 0, 255, 255];
+        blend(&mut dst, [1.0, 0.0, 0.0, 0.25]);
+        assert_eq!(dst, [64, 0, 191, 255]);
+    }
+
+    #[test]
+    fn scissor_clips() {
+        let scissor = Rect {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 2,
+        };
+        let pixels = rasterize_scissored(&quad(-1.0, -1.0, 1.0, 1.0, RED), 4, 4, scissor);
+        for y in 0..4 {
+            for x in 0..4 {
+                let inside = (1..3).contains(&x) && (1..3).contains(&y);
+                let expected = if inside { [128, 0, 0, 128] } else { [0; 4] };
+                assert_eq!(pixels[(y * 4 + x) * 4..][..4], expected, "{} {}", x, y);
+            }
+        }
+
+        let pixels = rasterize_scissored(&quad(-1.0, -1.0, 1.0, 1.0, RED), 4, 4, Rect::EMPTY);
+        assert!(pixels.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn scissored_into_only_touches_the_scissor() {
+        let scissor = Rect {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 2,
+        };
+        // leftovers from an earlier drawing: cleared inside, untouched outside
+        for vertices in [vec![], quad(-1.0, -1.0, 1.0, 1.0, RED)] {
+            let mut pixels = vec![255; 4 * 4 * 4];
+            rasterize_scissored_into(&mut pixels, &vertices, 4, 4, scissor);
+            let drawn = if vertices.is_empty() {
+                [0; 4]
+            } else {
+                [128, 0, 0, 128]
+            };
+            for y in 0..4 {
+                for x in 0..4 {
+                    let inside = (1..3).contains(&x) && (1..3).contains(&y);
+                    let expected = if inside { drawn } else { [255; 4] };
+                    assert_eq!(pixels[(y * 4 + x) * 4..][..4], expected, "{} {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn errors_of_known_pixels() {
+        let source = [0, 0, 0, 255, 10, 20, 30, 255];
+        let drawing = [3, 4, 0, 255, 10, 20, 30, 255];
+        let errors = calculate_error(&source, &drawing, None, 2, ErrorMetric::Rgb);
+        assert!((errors[0] - 5.0).abs() < 1e-4);
+        assert_eq!(errors[1], 0.0);
+
+        // transparent black vs opaque black: 0 over black, 255 per channel over white
+        let errors = calculate_error(&[0, 0, 0, 0], &[0, 0, 0, 255], None, 1, ErrorMetric::Rgb);
+        assert!((errors[0] - MAX_ERROR_PER_PIXEL * 0.5).abs() < 1e-3);
+
+        let mask = [51, 255];
+        let errors = calculate_error(&source, &drawing, Some(&mask), 2, ErrorMetric::Rgb);
+        assert!((errors[0] - 1.0).abs() < 1e-4);
+
+        let rect = Rect {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        };
+        let total = calculate_error_in(&source, &drawing, None, 2, ErrorMetric::Rgb, rect);
+        assert!((total - 5.0).abs() < 1e-4);
+    }
+}
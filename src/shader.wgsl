@@ -1,11 +1,17 @@
 struct VertexInput {
     @location(0) position: vec4<f32>,
     @location(1) color : vec4<f32>,
+    @location(2) shape: vec4<f32>,
+    @location(3) gradient: vec4<f32>,
+    @location(4) gradient_color: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) color: vec4<f32>,
+    @location(1) shape: vec4<f32>, // see Vertex in lib.rs
+    @location(2) gradient: vec4<f32>,
+    @location(3) gradient_color: vec4<f32>,
 };
 
 @vertex
@@ -13,10 +19,55 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     var out : VertexOutput;
     out.position = in.position;
     out.color = in.color;
+    out.shape = in.shape;
+    out.gradient = in.gradient;
+    out.gradient_color = in.gradient_color;
     return out;
 }
 
+// rasterizer.rs mirrors this, keep them in sync
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return in.color;
-}
\ No newline at end of file
+    // derivatives have to be taken in uniform control flow, shape is affine across the quad so they're exact
+    let dx = dpdx(in.shape.xy);
+    let dy = dpdy(in.shape.xy);
+    var color = in.color;
+    if (in.shape.w != 0.0) {
+        color = gradient_color(in);
+    }
+    if (in.shape.z == 0.0) {
+        return color;
+    }
+    return vec4<f32>(color.rgb, color.a * ellipse_coverage(in.shape.xy, dx, dy));
+}
+
+// from color to gradient_color, premultiplied in between like Canvas2D, straight alpha out for the BlendState
+fn gradient_color(in: VertexOutput) -> vec4<f32> {
+    let p = in.shape.xy;
+    let g = in.gradient;
+    var t: f32;
+    if (in.shape.w < 1.5) { // interpolated, not exactly 1 or 2
+        let d = g.zw - g.xy;
+        t = dot(p - g.xy, d) / max(dot(d, d), 1e-12);
+    } else {
+        t = distance(p, g.xy) / max(g.z, 1e-6);
+    }
+    t = clamp(t, 0.0, 1.0);
+    let c0 = in.color;
+    let c1 = in.gradient_color;
+    let a = c0.a + (c1.a - c0.a) * t; // not mix(), same rounding as rasterizer.rs
+    let rgb = (c0.rgb * c0.a + (c1.rgb * c1.a - c0.rgb * c0.a) * t) / max(a, 1e-6);
+    return vec4<f32>(rgb, a);
+}
+
+// the ellipse's edge is the unit circle in shape.xy, distance to it in pixels approximated by
+// f / |gradient f| with f = length(uv) - 1, anti-aliased over one pixel
+fn ellipse_coverage(uv: vec2<f32>, dx: vec2<f32>, dy: vec2<f32>) -> f32 {
+    let r = length(uv);
+    if (r < 0.5) {
+        return 1.0; // deep inside, also avoids dividing by 0 in the center
+    }
+    let gradient = vec2<f32>(dot(uv, dx), dot(uv, dy)) / r;
+    let d = (r - 1.0) / max(length(gradient), 1e-6);
+    return clamp(0.5 - d, 0.0, 1.0);
+}
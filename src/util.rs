@@ -1,12 +1,12 @@
 use std::mem::size_of;
 
 use log::info;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{JsCast, JsError};
 use web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement, ImageData};
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 
-use crate::model::settings::MAX_ERROR_PER_PIXEL;
+use crate::model::color::Color;
 
 pub struct Timer<'a> {
     name: &'a str,
@@ -43,21 +43,8 @@ pub fn draw_buffer(buffer: &Vec<u8>, canvas: &HtmlCanvasElement) {
     let ctx = get_context(&canvas);
     let bd = BufferDimensions::new(w, h);
 
-    let clamped: wasm_bindgen::Clamped<&[u8]>;
-    let mut actual_data = vec![];
-    if bd.padded_bytes_per_row == bd.unpadded_bytes_per_row || buffer.len() == w * h * 4 {
-        // no padding has been added, can use directly
-        clamped = wasm_bindgen::Clamped(&buffer);
-    } else {
-        // copy out our actual data and ignore the padding that has been added to the gpu buffer
-        actual_data.reserve(bd.unpadded_bytes_per_row * h as usize);
-        for i in 0..h {
-            let start_index = (i * bd.padded_bytes_per_row) as usize;
-            let end_index = start_index + bd.unpadded_bytes_per_row;
-            actual_data.extend_from_slice(&buffer[start_index..end_index]);
-        }
-        clamped = wasm_bindgen::Clamped(&actual_data);
-    }
+    let actual_data = bd.unpad(buffer);
+    let clamped = wasm_bindgen::Clamped(actual_data.as_slice());
 
     let image_data =
         ImageData::new_with_u8_clamped_array_and_sh(clamped, w as u32, h as u32).unwrap();
@@ -85,23 +72,60 @@ pub async fn draw_on_canvas_internal(bytes: &Vec<u8>, canvas_id: &str) {
 
 // we are now calculating sqrt(((re * re) + (ge * ge) + (be * be))) in the gpu
 // error_buffer is raw bytes straight out of the gpu so need to convert chunks of 4 back into f32
-pub fn calculate_error_from_gpu(error_buffer: &Vec<u8>) -> (f32, Vec<u8>) {
-    let error_buffer_f32: Vec<f32> = error_buffer
+pub fn errors_from_gpu(error_buffer: &[u8]) -> Vec<f32> {
+    error_buffer
         .chunks_exact(4)
         .map(|c| f32::from_ne_bytes(c.try_into().unwrap()))
-        .collect();
-
-    let mut error_heatmap: Vec<u8> = Vec::with_capacity(error_buffer.len() * 4);
-    let mut error: f32 = 0.0;
-    error_buffer_f32.into_iter().for_each(|sqrt| {
-        // info!("error = {}", sqrt);
-        error += sqrt;
-        let err_color = f32::floor(255.0 * (1.0 - sqrt / MAX_ERROR_PER_PIXEL)) as u8;
+        .collect()
+}
+
+// average color of straight alpha RGBA8 pixels, weighted by their alpha, always opaque
+pub fn mean_color(pixels: &[u8]) -> Color {
+    let mut sum = [0u64; 4];
+    for p in pixels.chunks_exact(4) {
+        let a = p[3] as u64;
+        for i in 0..3 {
+            sum[i] += p[i] as u64 * a;
+        }
+        sum[3] += a;
+    }
+    if sum[3] == 0 {
+        return Color::WHITE;
+    }
+    let [r, g, b] = [0, 1, 2].map(|i| ((sum[i] + sum[3] / 2) / sum[3]) as u8);
+    Color { r, g, b, a: 255 }
+}
+
+// renders come out premultiplied (see the BlendState), canvases and image files want straight alpha
+pub fn premultiply(pixels: &mut [u8]) {
+    for p in pixels.chunks_exact_mut(4) {
+        let a = p[3] as u32;
+        for c in &mut p[..3] {
+            *c = ((*c as u32 * a + 127) / 255) as u8;
+        }
+    }
+}
+
+pub fn unpremultiply(pixels: &mut [u8]) {
+    for p in pixels.chunks_exact_mut(4) {
+        let a = p[3] as u32;
+        if a == 0 || a == 255 {
+            continue;
+        }
+        for c in &mut p[..3] {
+            *c = ((*c as u32 * 255 + a / 2) / a).min(255) as u8;
+        }
+    }
+}
+
+// white = no error, red = max error (see ErrorMetric::max_error_per_pixel)
+pub fn error_heatmap(errors: &[f32], max_error: f32) -> Vec<u8> {
+    let mut error_heatmap: Vec<u8> = Vec::with_capacity(errors.len() * 4);
+    errors.iter().for_each(|&sqrt| {
+        let err_color = f32::floor(255.0 * (1.0 - sqrt / max_error)) as u8;
         error_heatmap.extend_from_slice(&[255, err_color, err_color, 255]);
     });
-
-    // info!("{:?}", error_heatmap);
-    return (error, error_heatmap);
+    error_heatmap
 }
 
 pub fn check_error_calcs(
@@ -142,18 +166,33 @@ pub fn check_error_calcs(
     log::info!("{} vs {}", error1, error2);
 }
 
-pub fn randomf32_clamped(min: f32, max: f32) -> f32 {
+// every random decision goes through the engine's instance so a run can be replayed from its seed
+pub type SeededRng = rand_pcg::Pcg32;
+
+// what exported fns throw in JS instead of panicking, which would take down the whole module
+pub fn to_js_error(error: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", error))
+}
+
+pub fn seeded_rng(seed: u32) -> SeededRng {
+    SeededRng::seed_from_u64(seed as u64)
+}
+
+pub fn randomf32_clamped(min: f32, max: f32, rng: &mut SeededRng) -> f32 {
     assert!(min < max); // saw a 'cannot sample empty range' error but haven't hit this so far
-    return rand::thread_rng().gen_range(min..max);
+    return rng.gen_range(min..max);
 }
 
-pub async fn get_bytes(output_buffer: &wgpu::Buffer) -> Vec<u8> {
+pub async fn get_bytes(device: &wgpu::Device, output_buffer: &wgpu::Buffer) -> Vec<u8> {
     let buffer_slice = output_buffer.slice(..);
 
     // Sets the buffer up for mapping, sending over the result of the mapping back to us when it is finished.
     let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
     buffer_slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
 
+    // native backends only fire the map_async callback when the device is polled, no-op on the web
+    device.poll(wgpu::Maintain::Wait);
+
     if let Some(Ok(())) = receiver.receive().await {
         let padded_buffer = buffer_slice.get_mapped_range();
         let vec = padded_buffer.to_vec();
@@ -187,4 +226,22 @@ impl BufferDimensions {
             padded_bytes_per_row,
         }
     }
+
+    // copy out our actual data and ignore the padding that has been added to the gpu buffer
+    pub fn unpad(&self, buffer: &[u8]) -> Vec<u8> {
+        let unpadded_len = self.unpadded_bytes_per_row * self.height;
+        if self.padded_bytes_per_row == self.unpadded_bytes_per_row || buffer.len() == unpadded_len
+        {
+            // no padding has been added, can use directly
+            return buffer.to_vec();
+        }
+
+        let mut actual_data = Vec::with_capacity(unpadded_len);
+        for i in 0..self.height {
+            let start_index = i * self.padded_bytes_per_row;
+            let end_index = start_index + self.unpadded_bytes_per_row;
+            actual_data.extend_from_slice(&buffer[start_index..end_index]);
+        }
+        actual_data
+    }
 }